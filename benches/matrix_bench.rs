@@ -1,6 +1,18 @@
 #![allow(unused_must_use)]
-use criterion::{criterion_group, criterion_main, Criterion};
-use ray_tracer::matrix::Matrix;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ray_tracer::matrix::{Matrix, Matrix4};
+use ray_tracer::tuple::Tuple;
+
+const SAMPLE: [[f64; 4]; 4] = [
+    [-5.0, 2.0, 6.0, -8.0],
+    [1.0, -5.0, 1.0, 8.0],
+    [7.0, 7.0, -6.0, -7.0],
+    [1.0, -3.0, 7.0, 4.0],
+];
+
+fn sample() -> Matrix {
+    Matrix::new(SAMPLE.iter().map(|row| row.to_vec()).collect())
+}
 
 fn transpose() {
     let m = Matrix::identity_matrix();
@@ -36,5 +48,36 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("matrix multiply", |b| b.iter(multiply));
 }
 
-criterion_group!(benches, criterion_benchmark);
+fn comparison_benchmark(c: &mut Criterion) {
+    let general = sample();
+    let fixed = Matrix4::new(SAMPLE);
+    let t = Tuple::point(1.0, 2.0, 3.0);
+
+    c.bench_function("general inverse", |b| {
+        b.iter(|| black_box(&general).inverse())
+    });
+    c.bench_function("matrix4 inverse", |b| {
+        b.iter(|| black_box(&fixed).inverse())
+    });
+    c.bench_function("general multiply", |b| {
+        b.iter(|| black_box(&general).matrix_multiply(black_box(&general)))
+    });
+    c.bench_function("matrix4 multiply", |b| {
+        b.iter(|| black_box(&fixed).matrix_multiply(black_box(&fixed)))
+    });
+    c.bench_function("general transpose", |b| {
+        b.iter(|| black_box(&general).transpose())
+    });
+    c.bench_function("matrix4 transpose", |b| {
+        b.iter(|| black_box(&fixed).transpose())
+    });
+    c.bench_function("general tuple multiply", |b| {
+        b.iter(|| black_box(&general).tuple_multiply(black_box(&t)))
+    });
+    c.bench_function("matrix4 tuple multiply", |b| {
+        b.iter(|| black_box(&fixed).tuple_multiply(black_box(&t)))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark, comparison_benchmark);
 criterion_main!(benches);
@@ -2,12 +2,17 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use ray_tracer::canvas::Canvas;
 use ray_tracer::color::Color;
-use ray_tracer::ppm_printer::PpmPrinter;
+use ray_tracer::ppm_printer::{PpmFormat, PpmPrinter};
+use std::io;
 
 fn ppm_printer(image: &Canvas) {
     PpmPrinter::pixel_data(image);
 }
 
+fn ppm_writer(image: &Canvas, format: PpmFormat) {
+    PpmPrinter::write(image, format, io::sink());
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let width = 100;
     let height = 50;
@@ -21,6 +26,12 @@ fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("ppm_printer", |b| {
         b.iter(|| ppm_printer(black_box(&canvas)))
     });
+    c.bench_function("ppm_writer ascii", |b| {
+        b.iter(|| ppm_writer(black_box(&canvas), PpmFormat::Ascii))
+    });
+    c.bench_function("ppm_writer binary", |b| {
+        b.iter(|| ppm_writer(black_box(&canvas), PpmFormat::Binary))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,96 @@
+use crate::color::Color;
+use crate::sampling::SampleRng;
+use crate::tuple::Tuple;
+
+/// A rectangular light, spanned by two edges from one corner and divided into a grid of
+/// cells. Shadows are tested against one point in each cell, so points that can see only
+/// part of the light fall in a penumbra.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AreaLight {
+    pub corner: Tuple,
+    /// One cell's extent along the first edge.
+    pub uvec: Tuple,
+    pub usteps: usize,
+    /// One cell's extent along the second edge.
+    pub vvec: Tuple,
+    pub vsteps: usize,
+    /// The centre of the light.
+    pub position: Tuple,
+    pub intensity: Color,
+    /// When set, each cell is sampled at a random point instead of its centre, which
+    /// replaces the banding of a regular grid with noise.
+    pub jitter: bool,
+    pub id: usize,
+}
+
+impl AreaLight {
+    /// # Panics
+    ///
+    /// Will panic if either edge is divided into zero steps
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::similar_names)]
+    pub fn new(
+        corner: Tuple,
+        full_uvec: Tuple,
+        usteps: usize,
+        full_vvec: Tuple,
+        vsteps: usize,
+        intensity: Color,
+    ) -> Self {
+        assert!(
+            usteps > 0 && vsteps > 0,
+            "an area light needs at least one cell"
+        );
+        AreaLight {
+            corner,
+            uvec: full_uvec / usteps as f64,
+            usteps,
+            vvec: full_vvec / vsteps as f64,
+            vsteps,
+            position: corner + full_uvec * 0.5 + full_vvec * 0.5,
+            intensity,
+            jitter: false,
+            id: 0,
+        }
+    }
+
+    /// The number of points shadows are tested against, one per cell.
+    #[must_use]
+    pub fn samples(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    pub fn set_jitter(&mut self, jitter: bool) {
+        self.jitter = jitter;
+    }
+
+    /// Returns the point `ju` and `jv` of the way across cell (`u`, `v`).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn point_on_light(&self, u: usize, v: usize, ju: f64, jv: f64) -> Tuple {
+        self.corner + self.uvec * (u as f64 + ju) + self.vvec * (v as f64 + jv)
+    }
+
+    /// Returns one point in every cell of the light, as seen from `from`. Without jitter
+    /// these are the cell centres. With it, the points are random but derived from `from`,
+    /// so a render always comes out the same.
+    #[must_use]
+    pub fn sample_points(&self, from: Tuple) -> Vec<Tuple> {
+        let mut rng = SampleRng::new(
+            from.x.to_bits() ^ from.y.to_bits().rotate_left(21) ^ from.z.to_bits().rotate_left(42),
+        );
+        let mut points = Vec::with_capacity(self.samples());
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                let (ju, jv) = if self.jitter {
+                    (rng.next_f64(), rng.next_f64())
+                } else {
+                    (0.5, 0.5)
+                };
+                points.push(self.point_on_light(u, v, ju, jv));
+            }
+        }
+        points
+    }
+}
@@ -0,0 +1,140 @@
+use crate::ray::Ray;
+use crate::transform::Transform;
+use crate::tuple::Tuple;
+use crate::EPSILON;
+
+/// An axis-aligned box enclosing a shape. A box is empty when its minimum lies above its
+/// maximum, and unbounded shapes such as planes are given infinite extents.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BoundingBox {
+    pub min: Tuple,
+    pub max: Tuple,
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl BoundingBox {
+    #[must_use]
+    pub fn new(min: Tuple, max: Tuple) -> Self {
+        BoundingBox { min, max }
+    }
+
+    #[must_use]
+    pub fn empty() -> Self {
+        BoundingBox {
+            min: Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    #[must_use]
+    pub fn infinite() -> Self {
+        BoundingBox {
+            min: Tuple::point(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: Tuple::point(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    #[must_use]
+    pub fn is_finite(&self) -> bool {
+        [self.min, self.max]
+            .iter()
+            .all(|t| t.x.is_finite() && t.y.is_finite() && t.z.is_finite())
+    }
+
+    pub fn add_point(&mut self, p: Tuple) {
+        self.min = Tuple::point(
+            self.min.x.min(p.x),
+            self.min.y.min(p.y),
+            self.min.z.min(p.z),
+        );
+        self.max = Tuple::point(
+            self.max.x.max(p.x),
+            self.max.y.max(p.y),
+            self.max.z.max(p.z),
+        );
+    }
+
+    pub fn merge(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.add_point(other.min);
+            self.add_point(other.max);
+        }
+    }
+
+    #[must_use]
+    pub fn contains_point(&self, p: Tuple) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x)
+            && (self.min.y..=self.max.y).contains(&p.y)
+            && (self.min.z..=self.max.z).contains(&p.z)
+    }
+
+    #[must_use]
+    pub fn centroid(&self) -> Tuple {
+        Tuple::point(
+            f64::midpoint(self.min.x, self.max.x),
+            f64::midpoint(self.min.y, self.max.y),
+            f64::midpoint(self.min.z, self.max.z),
+        )
+    }
+
+    /// Returns the box enclosing this one after it has been transformed. Boxes that are not
+    /// finite stay unbounded, as their corners cannot be transformed meaningfully.
+    #[must_use]
+    pub fn transform(&self, t: &Transform) -> BoundingBox {
+        if self.is_empty() {
+            return *self;
+        }
+        if !self.is_finite() {
+            return BoundingBox::infinite();
+        }
+        let mut result = BoundingBox::empty();
+        for x in [self.min.x, self.max.x] {
+            for y in [self.min.y, self.max.y] {
+                for z in [self.min.z, self.max.z] {
+                    result.add_point(t.transform(&Tuple::point(x, y, z)));
+                }
+            }
+        }
+        result
+    }
+
+    /// Tests the whole line the ray lies on against the box, so intersections behind the
+    /// ray's origin are never culled. The box is padded slightly to stay conservative.
+    #[must_use]
+    pub fn intersects(&self, ray: &Ray) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let axes = [
+            (ray.origin.x, ray.direction.x, self.min.x, self.max.x),
+            (ray.origin.y, ray.direction.y, self.min.y, self.max.y),
+            (ray.origin.z, ray.direction.z, self.min.z, self.max.z),
+        ];
+        let mut tmin = f64::NEG_INFINITY;
+        let mut tmax = f64::INFINITY;
+        for (origin, direction, min, max) in axes {
+            let (min, max) = (min - EPSILON, max + EPSILON);
+            if direction == 0.0 {
+                if origin < min || origin > max {
+                    return false;
+                }
+                continue;
+            }
+            let t1 = (min - origin) / direction;
+            let t2 = (max - origin) / direction;
+            tmin = tmin.max(t1.min(t2));
+            tmax = tmax.min(t1.max(t2));
+        }
+        tmin <= tmax
+    }
+}
@@ -0,0 +1,107 @@
+use crate::bounds::BoundingBox;
+use crate::ray::Ray;
+
+const MAX_LEAF_SIZE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Leaf(Vec<(usize, BoundingBox)>),
+    Interior(usize, usize),
+}
+
+/// A bounding volume hierarchy over a list of shapes, used to find which of them a ray
+/// could possibly hit without testing every one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bvh {
+    nodes: Vec<(BoundingBox, Node)>,
+    root: Option<usize>,
+    // Shapes with infinite bounds, such as planes, which every ray has to be tested against.
+    unbounded: Vec<usize>,
+}
+
+impl Bvh {
+    /// Builds a hierarchy over shapes with the given bounds. Items are referred to by their
+    /// position in `bounds`.
+    #[must_use]
+    pub fn build(bounds: &[BoundingBox]) -> Self {
+        let mut bvh = Bvh {
+            nodes: vec![],
+            root: None,
+            unbounded: vec![],
+        };
+        let mut items = vec![];
+        for (idx, b) in bounds.iter().enumerate() {
+            if b.is_empty() {
+                continue;
+            }
+            if b.is_finite() {
+                items.push((idx, *b));
+            } else {
+                bvh.unbounded.push(idx);
+            }
+        }
+        if !items.is_empty() {
+            bvh.root = Some(bvh.build_node(&mut items));
+        }
+        bvh
+    }
+
+    /// Returns the items whose bounds the ray's line passes through, in ascending order.
+    #[must_use]
+    pub fn candidates(&self, ray: &Ray) -> Vec<usize> {
+        let mut result = self.unbounded.clone();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+        while let Some(idx) = stack.pop() {
+            let (bounds, node) = &self.nodes[idx];
+            if !bounds.intersects(ray) {
+                continue;
+            }
+            match node {
+                Node::Leaf(items) => result.extend(
+                    items
+                        .iter()
+                        .filter(|(_, b)| b.intersects(ray))
+                        .map(|(i, _)| *i),
+                ),
+                Node::Interior(left, right) => {
+                    stack.push(*left);
+                    stack.push(*right);
+                }
+            }
+        }
+        result.sort_unstable();
+        result
+    }
+
+    // Splits the items at the median of their centroids along the axis where the centroids
+    // are most spread out, returning the index of the new node.
+    fn build_node(&mut self, items: &mut [(usize, BoundingBox)]) -> usize {
+        let mut bounds = BoundingBox::empty();
+        let mut centroids = BoundingBox::empty();
+        for (_, b) in items.iter() {
+            bounds.merge(b);
+            centroids.add_point(b.centroid());
+        }
+
+        if items.len() <= MAX_LEAF_SIZE {
+            self.nodes.push((bounds, Node::Leaf(items.to_vec())));
+            return self.nodes.len() - 1;
+        }
+
+        let extent = centroids.max - centroids.min;
+        let key: fn(&BoundingBox) -> f64 = if extent.x >= extent.y && extent.x >= extent.z {
+            |b| b.centroid().x
+        } else if extent.y >= extent.z {
+            |b| b.centroid().y
+        } else {
+            |b| b.centroid().z
+        };
+        items.sort_unstable_by(|a, b| key(&a.1).total_cmp(&key(&b.1)));
+
+        let (left_items, right_items) = items.split_at_mut(items.len() / 2);
+        let left = self.build_node(left_items);
+        let right = self.build_node(right_items);
+        self.nodes.push((bounds, Node::Interior(left, right)));
+        self.nodes.len() - 1
+    }
+}
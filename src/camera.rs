@@ -1,9 +1,18 @@
+use crate::color::Color;
+use crate::progress::{
+    CancellationToken, NoProgress, RenderCancelled, RenderObserver, RenderProgress,
+};
 use crate::ray::Ray;
-use crate::transform::Transform;
+use crate::sampling::{AdaptiveSampling, AdaptiveStats, Sampler};
+use crate::transform::{DegenerateTransform, Transform};
 use crate::tuple::Tuple;
 use crate::world::World;
 use crate::{canvas::Canvas, MAX_REFLECT_DEPTH};
-use std::io::{stdout, Write};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Instant;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Camera {
@@ -13,7 +22,9 @@ pub struct Camera {
     pub half_height: f64,
     pub field_of_view: f64,
     pub pixel_size: f64,
-    pub transform: Transform,
+    transform: Transform,
+    inverse: Transform,
+    sampler: Sampler,
 }
 
 impl Camera {
@@ -28,6 +39,8 @@ impl Camera {
             vsize,
             field_of_view,
             transform: Transform::new(),
+            inverse: Transform::new(),
+            sampler: Sampler::default(),
             half_width: half_view * aspect,
             half_height: half_view,
             pixel_size: 0.0,
@@ -41,14 +54,49 @@ impl Camera {
         c
     }
 
+    #[must_use]
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    /// # Errors
+    ///
+    /// Will return `DegenerateTransform::Camera` if the transform cannot be inverted, in which
+    /// case the camera is left unchanged
+    pub fn set_transform(&mut self, transform: Transform) -> Result<(), DegenerateTransform> {
+        self.inverse = transform
+            .try_inverse()
+            .map_err(|_| DegenerateTransform::Camera)?;
+        self.transform = transform;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn sampler(&self) -> &Sampler {
+        &self.sampler
+    }
+
+    /// Sets how many rays are cast through each pixel when rendering and how they are
+    /// combined.
+    pub fn set_sampler(&mut self, sampler: Sampler) {
+        self.sampler = sampler;
+    }
+
     #[must_use]
     pub fn ray_for_pixel(&self, px: f64, py: f64) -> Ray {
-        let xoffset = (px + 0.5) * self.pixel_size;
-        let yoffset = (py + 0.5) * self.pixel_size;
+        self.ray_for_subpixel(px, py, 0.5, 0.5)
+    }
+
+    /// Returns the ray through the point `dx` and `dy` pixels across and down from the top
+    /// left corner of pixel (`px`, `py`).
+    #[must_use]
+    pub fn ray_for_subpixel(&self, px: f64, py: f64, dx: f64, dy: f64) -> Ray {
+        let xoffset = (px + dx) * self.pixel_size;
+        let yoffset = (py + dy) * self.pixel_size;
         let world_x = self.half_width - xoffset;
         let world_y = self.half_height - yoffset;
 
-        let t = self.transform.clone().inverse();
+        let t = &self.inverse;
         let pixel = t.transform(&Tuple::point(world_x, world_y, -1.0));
         let origin = t.transform(&Tuple::point(0.0, 0.0, 0.0));
         let direction = (pixel - origin).normalize();
@@ -56,35 +104,249 @@ impl Camera {
         Ray::new(origin, direction)
     }
 
+    /// Renders the world using one worker thread per available core.
+    #[must_use]
+    pub fn render(&self, world: &World) -> Canvas {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        self.render_with_threads(world, threads)
+    }
+
+    /// Renders the world on `threads` worker threads without reporting progress.
+    ///
     /// # Panics
     ///
-    /// Will panic if writing to stdout fails
+    /// Will panic if `threads` is zero
+    #[must_use]
+    pub fn render_with_threads(&self, world: &World, threads: usize) -> Canvas {
+        self.render_observed(world, threads, &mut NoProgress, &CancellationToken::new())
+            .expect("render cannot be cancelled without access to its token")
+    }
+
+    /// Renders the world by handing out scanlines to `threads` worker threads, which share
+    /// the world immutably. Every pixel is colored by exactly the same computation whatever
+    /// the thread count, so the resulting canvas does not depend on it. The observer is told
+    /// about each finished row, and workers stop picking up rows once `cancel` is set. They
+    /// can only get a few rows ahead of the observer, so a cancellation made while handling
+    /// a row takes effect promptly.
+    ///
+    /// # Errors
+    ///
+    /// Will return `RenderCancelled` if the token was cancelled before every row was rendered
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `threads` is zero
     #[allow(clippy::cast_possible_truncation)]
     #[allow(clippy::cast_sign_loss)]
-    #[allow(clippy::cast_precision_loss)]
-    #[must_use]
-    pub fn render(&self, world: &World) -> Canvas {
-        let num_pixels = (self.hsize * self.vsize) as usize;
-        let mut pixels_colored: usize = 0;
-        let mut progress_string = String::new();
-        let mut image = Canvas::new(self.hsize as usize, self.vsize as usize);
-        println!("generating data for {} pixels", num_pixels);
-        for y in 0..self.vsize as usize {
-            for x in 0..self.hsize as usize {
-                let r = self.ray_for_pixel(x as f64, y as f64);
-                let color = world.color_at(&r, MAX_REFLECT_DEPTH);
-                pixels_colored += 1;
-                let percent_done = (pixels_colored * 100 / num_pixels * 100) / 100;
-                while percent_done / 2 > progress_string.len() {
-                    progress_string += "=";
-                    print!("\r[{}>] {}%", progress_string, percent_done);
-                    stdout().flush().unwrap();
+    pub fn render_observed(
+        &self,
+        world: &World,
+        threads: usize,
+        observer: &mut dyn RenderObserver,
+        cancel: &CancellationToken,
+    ) -> Result<Canvas, RenderCancelled> {
+        assert!(threads > 0, "cannot render with zero threads");
+        let (width, height) = (self.hsize as usize, self.vsize as usize);
+        let start = Instant::now();
+        let mut progress = RenderProgress {
+            total_pixels: width * height,
+            total_rows: height,
+            ..RenderProgress::default()
+        };
+        let mut image = Canvas::new(width, height);
+        observer.started(&progress);
+
+        Camera::for_each_row(
+            height,
+            threads,
+            cancel,
+            |y| self.render_row(world, y, width),
+            |y, row| {
+                for (x, color) in row.into_iter().enumerate() {
+                    image.write_pixel(x, y, color);
                 }
+                progress.rows_done += 1;
+                progress.pixels_done += width;
+                progress.elapsed = start.elapsed();
+                observer.row_finished(&progress);
+            },
+        );
+
+        if progress.rows_done < height {
+            return Err(RenderCancelled);
+        }
+        progress.elapsed = start.elapsed();
+        observer.finished(&progress);
+        Ok(image)
+    }
+
+    /// Renders the world with one sample through the centre of every pixel, then looks for
+    /// pixels whose color differs from any of their eight neighbours by more than the
+    /// threshold in `settings`. Only those pixels are refined, by sampling the four quarters
+    /// of the pixel and recursively subdividing any quarter whose samples still disagree, up
+    /// to the maximum depth. The camera's own sampler is not used.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `threads` is zero
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn render_adaptive(
+        &self,
+        world: &World,
+        threads: usize,
+        settings: &AdaptiveSampling,
+    ) -> (Canvas, AdaptiveStats) {
+        assert!(threads > 0, "cannot render with zero threads");
+        let (width, height) = (self.hsize as usize, self.vsize as usize);
+        let mut centres = self.clone();
+        centres.set_sampler(Sampler::default());
+        let mut image = centres.render_with_threads(world, threads);
 
-                image.write_pixel(x, y, color);
+        let mut stats = AdaptiveStats {
+            pixels: width * height,
+            samples: width * height,
+            ..AdaptiveStats::default()
+        };
+        let rough = &image;
+        let needs_refinement = |x: usize, y: usize| {
+            if settings.max_depth == 0 {
+                return false;
             }
+            let color = rough.pixel_at(x, y);
+            (y.saturating_sub(1)..(y + 2).min(height)).any(|ny| {
+                (x.saturating_sub(1)..(x + 2).min(width))
+                    .any(|nx| settings.differs(color, rough.pixel_at(nx, ny)))
+            })
+        };
+        let mut updates = Vec::new();
+        Camera::for_each_row(
+            height,
+            threads,
+            &CancellationToken::new(),
+            |y| {
+                let mut refiner = Refiner {
+                    camera: self,
+                    world,
+                    settings,
+                    samples: 0,
+                };
+                let colors: Vec<_> = (0..width)
+                    .filter(|x| needs_refinement(*x, y))
+                    .map(|x| (x, refiner.pixel(x, y)))
+                    .collect();
+                (colors, refiner.samples)
+            },
+            |y, (colors, samples)| {
+                stats.refined_pixels += colors.len();
+                stats.samples += samples;
+                updates.extend(colors.into_iter().map(|(x, color)| (x, y, color)));
+            },
+        );
+        for (x, y, color) in updates {
+            image.write_pixel(x, y, color);
+        }
+        (image, stats)
+    }
+
+    // Hands out the rows from 0 to `height` to `threads` worker threads, which compute each
+    // one with `render`. The results are passed to `finished` on the calling thread as they
+    // arrive. Workers stop picking up rows once `cancel` is set.
+    fn for_each_row<T: Send>(
+        height: usize,
+        threads: usize,
+        cancel: &CancellationToken,
+        render: impl Fn(usize) -> T + Sync,
+        mut finished: impl FnMut(usize, T),
+    ) {
+        let next_row = AtomicUsize::new(0);
+        thread::scope(|scope| {
+            let (sender, receiver) = mpsc::sync_channel(threads);
+            for _ in 0..threads.min(height.max(1)) {
+                let sender = sender.clone();
+                let (next_row, render) = (&next_row, &render);
+                scope.spawn(move || loop {
+                    if cancel.is_cancelled() {
+                        break;
+                    }
+                    let y = next_row.fetch_add(1, Ordering::Relaxed);
+                    if y >= height {
+                        break;
+                    }
+                    if sender.send((y, render(y))).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            for (y, row) in receiver {
+                finished(y, row);
+            }
+        });
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn render_row(&self, world: &World, y: usize, width: usize) -> Vec<Color> {
+        (0..width)
+            .map(|x| {
+                let offsets = self.sampler.offsets(x, y);
+                let colors: Vec<Color> = offsets
+                    .iter()
+                    .map(|(dx, dy)| {
+                        let r = self.ray_for_subpixel(x as f64, y as f64, *dx, *dy);
+                        world.color_at(&r, MAX_REFLECT_DEPTH)
+                    })
+                    .collect();
+                self.sampler.combine(&offsets, &colors)
+            })
+            .collect()
+    }
+}
+
+// Recursively samples a single pixel for adaptive rendering, counting the rays it casts.
+struct Refiner<'a> {
+    camera: &'a Camera,
+    world: &'a World,
+    settings: &'a AdaptiveSampling,
+    samples: usize,
+}
+
+impl Refiner<'_> {
+    #[allow(clippy::cast_precision_loss)]
+    fn pixel(&mut self, x: usize, y: usize) -> Color {
+        self.region((x as f64, y as f64), (0.0, 0.0), 1.0, 1)
+    }
+
+    // Samples the centres of the four quarters of the square region with its top left
+    // corner at `corner` and sides `size` pixels long, subdividing the quarters if their
+    // colors disagree and the maximum depth has not been reached.
+    fn region(&mut self, pixel: (f64, f64), corner: (f64, f64), size: f64, depth: usize) -> Color {
+        let half = size / 2.0;
+        let quarters = [
+            corner,
+            (corner.0 + half, corner.1),
+            (corner.0, corner.1 + half),
+            (corner.0 + half, corner.1 + half),
+        ];
+        let mut colors = quarters.map(|(left, top)| {
+            let r =
+                self.camera
+                    .ray_for_subpixel(pixel.0, pixel.1, left + half / 2.0, top + half / 2.0);
+            self.world.color_at(&r, MAX_REFLECT_DEPTH)
+        });
+        self.samples += 4;
+
+        let uniform = colors
+            .iter()
+            .all(|color| !self.settings.differs(colors[0], *color));
+        if !uniform && depth < self.settings.max_depth {
+            colors = quarters.map(|quarter| self.region(pixel, quarter, half, depth + 1));
         }
-        println!();
-        image
+        colors
+            .into_iter()
+            .fold(Color::new(0.0, 0.0, 0.0), |total, color| total + color)
+            * 0.25
     }
 }
@@ -1,5 +1,50 @@
 use crate::color::Color;
+use crate::hdr_image::{Pfm, RadianceHdr};
+use crate::png_encoder::{PngBitDepth, PngEncoder};
+use crate::ppm_printer::{PpmFormat, PpmPrinter};
+use crate::tone_map::ToneMap;
 
+use std::fmt;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// The image formats a canvas can be saved in, identified by file extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Ppm,
+    Png,
+    Pfm,
+    Hdr,
+}
+
+impl ImageFormat {
+    /// Returns the format named by the extension of `filepath`, ignoring case.
+    #[must_use]
+    pub fn from_path(filepath: &str) -> Option<ImageFormat> {
+        let extension = Path::new(filepath).extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "ppm" => Some(ImageFormat::Ppm),
+            "png" => Some(ImageFormat::Png),
+            "pfm" => Some(ImageFormat::Pfm),
+            "hdr" => Some(ImageFormat::Hdr),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UnsupportedImageFormat(pub String);
+
+impl fmt::Display for UnsupportedImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Cannot tell which image format to use for {}", self.0)
+    }
+}
+
+impl std::error::Error for UnsupportedImageFormat {}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Canvas {
     buffer: Vec<Color>,
     width: usize,
@@ -39,4 +84,46 @@ impl Canvas {
     pub fn pixels(&self) -> &Vec<Color> {
         &(self.buffer)
     }
+
+    /// Saves the canvas in the format named by the extension of `filepath`: a plain PPM file
+    /// for `.ppm`, an 8-bit PNG image for `.png`, or the unclamped colors as a Portable Float
+    /// Map for `.pfm` or a Radiance RGBE image for `.hdr`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the extension is not recognised, or if the file cannot be
+    /// opened or written to.
+    pub fn save(&self, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_tone_mapped(filepath, &ToneMap::default())
+    }
+
+    /// Saves the canvas like `save`, passing every pixel through `tone_map` when writing a
+    /// PPM or PNG file. PFM and Radiance HDR files keep the linear colors, leaving tone
+    /// mapping to whatever displays them.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the extension is not recognised, or if the file cannot be
+    /// opened or written to.
+    pub fn save_tone_mapped(
+        &self,
+        filepath: &str,
+        tone_map: &ToneMap,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let format = ImageFormat::from_path(filepath)
+            .ok_or_else(|| UnsupportedImageFormat(filepath.to_string()))?;
+        match format {
+            ImageFormat::Pfm => return Pfm::dump_to_file(self, filepath),
+            ImageFormat::Hdr => return RadianceHdr::dump_to_file(self, filepath),
+            ImageFormat::Ppm | ImageFormat::Png => {}
+        }
+        let mut file = BufWriter::new(File::create(filepath)?);
+        if format == ImageFormat::Ppm {
+            PpmPrinter::write_tone_mapped(self, PpmFormat::Ascii, tone_map, &mut file)?;
+        } else {
+            PngEncoder::write_tone_mapped(self, PngBitDepth::Eight, tone_map, &mut file)?;
+        }
+        file.flush()?;
+        Ok(())
+    }
 }
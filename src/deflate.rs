@@ -0,0 +1,181 @@
+// A small zlib encoder for the PNG writer. Data is compressed with LZ77 into a single
+// DEFLATE block using the fixed Huffman codes, which compresses rendered images well
+// without the bookkeeping of building dynamic code tables.
+
+const WINDOW_SIZE: usize = 32768;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LENGTH_BASES: [usize; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA_BITS: [u32; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASES: [usize; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA_BITS: [u32; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+// Packs values into bytes starting from the least significant bit, as DEFLATE requires.
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write_bits(&mut self, value: u32, count: u32) {
+        self.acc |= u64::from(value) << self.bits;
+        self.bits += count;
+        while self.bits >= 8 {
+            self.out.push((self.acc & 0xff) as u8);
+            self.acc >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    // Huffman codes are defined most significant bit first, so they are reversed before
+    // being packed.
+    fn write_code(&mut self, code: u32, length: u32) {
+        self.write_bits(code.reverse_bits() >> (32 - length), length);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.out.push((self.acc & 0xff) as u8);
+        }
+        self.out
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn write_literal(writer: &mut BitWriter, symbol: usize) {
+    let symbol = symbol as u32;
+    match symbol {
+        0..=143 => writer.write_code(0x30 + symbol, 8),
+        144..=255 => writer.write_code(0x190 + symbol - 144, 9),
+        256..=279 => writer.write_code(symbol - 256, 7),
+        _ => writer.write_code(0xc0 + symbol - 280, 8),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let code = LENGTH_BASES.partition_point(|base| *base <= length) - 1;
+    write_literal(writer, 257 + code);
+    writer.write_bits(
+        (length - LENGTH_BASES[code]) as u32,
+        LENGTH_EXTRA_BITS[code],
+    );
+
+    let code = DISTANCE_BASES.partition_point(|base| *base <= distance) - 1;
+    writer.write_code(code as u32, 5);
+    writer.write_bits(
+        (distance - DISTANCE_BASES[code]) as u32,
+        DISTANCE_EXTRA_BITS[code],
+    );
+}
+
+fn hash(data: &[u8], pos: usize) -> usize {
+    let key = u32::from(data[pos]) << 16 | u32::from(data[pos + 1]) << 8 | u32::from(data[pos + 2]);
+    (key.wrapping_mul(0x9e37_79b1) >> (32 - HASH_BITS)) as usize
+}
+
+fn insert(data: &[u8], head: &mut [usize], prev: &mut [usize], pos: usize) {
+    if pos + MIN_MATCH <= data.len() {
+        let h = hash(data, pos);
+        prev[pos % WINDOW_SIZE] = head[h];
+        head[h] = pos + 1;
+    }
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += u32::from(*byte);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+/// Compresses `data` into a zlib stream.
+pub(crate) fn zlib_compress(data: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter {
+        out: vec![0x78, 0x01],
+        acc: 0,
+        bits: 0,
+    };
+    // A single final block compressed with the fixed codes.
+    writer.write_bits(1, 1);
+    writer.write_bits(1, 2);
+
+    // `head` holds the most recent position with each hash, and `prev` links every position
+    // to the previous one with the same hash. Positions are stored offset by one so that
+    // zero means none.
+    let mut head = vec![0usize; 1 << HASH_BITS];
+    let mut prev = vec![0usize; WINDOW_SIZE];
+
+    let mut pos = 0;
+    while pos < data.len() {
+        let (mut best_length, mut best_distance) = (0, 0);
+        if pos + MIN_MATCH <= data.len() {
+            let max_length = MAX_MATCH.min(data.len() - pos);
+            let mut candidate = head[hash(data, pos)];
+            let mut chain = 0;
+            while candidate > 0 && chain < MAX_CHAIN {
+                let start = candidate - 1;
+                if pos - start > WINDOW_SIZE - 1 {
+                    break;
+                }
+                let length = data[start..]
+                    .iter()
+                    .zip(&data[pos..pos + max_length])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+                if length > best_length {
+                    best_length = length;
+                    best_distance = pos - start;
+                    if length == max_length {
+                        break;
+                    }
+                }
+                let next = prev[start % WINDOW_SIZE];
+                if next >= candidate {
+                    break;
+                }
+                candidate = next;
+                chain += 1;
+            }
+        }
+
+        if best_length >= MIN_MATCH {
+            write_match(&mut writer, best_length, best_distance);
+            for p in pos..pos + best_length {
+                insert(data, &mut head, &mut prev, p);
+            }
+            pos += best_length;
+        } else {
+            write_literal(&mut writer, usize::from(data[pos]));
+            insert(data, &mut head, &mut prev, pos);
+            pos += 1;
+        }
+    }
+    write_literal(&mut writer, 256);
+
+    let mut out = writer.finish();
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
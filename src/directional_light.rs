@@ -0,0 +1,36 @@
+use crate::color::Color;
+use crate::tuple::Tuple;
+
+/// A light infinitely far away, such as the sun, whose rays all travel in `direction`.
+/// It has no position, so the direction towards it is the same from every point and
+/// shadows are cast by anything in that direction, however far away.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DirectionalLight {
+    pub direction: Tuple,
+    pub intensity: Color,
+    pub id: usize,
+}
+
+impl DirectionalLight {
+    /// # Panics
+    ///
+    /// Will panic if `direction` has no length
+    #[must_use]
+    pub fn new(direction: Tuple, intensity: Color) -> Self {
+        assert!(
+            direction.magnitude() > 0.0,
+            "a directional light needs a direction to shine in"
+        );
+        DirectionalLight {
+            direction: direction.normalize(),
+            intensity,
+            id: 0,
+        }
+    }
+
+    /// Returns the direction from any point towards the light.
+    #[must_use]
+    pub fn lightv(&self) -> Tuple {
+        -self.direction
+    }
+}
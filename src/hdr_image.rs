@@ -0,0 +1,472 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+#[derive(Debug)]
+pub enum HdrError {
+    Io(std::io::Error),
+    UnsupportedFormat(String),
+    Malformed {
+        expected: &'static str,
+        found: String,
+    },
+    Truncated {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for HdrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HdrError::Io(e) => write!(f, "Could not read HDR image: {e}"),
+            HdrError::UnsupportedFormat(format) => {
+                write!(f, "Unsupported image format {format:?}")
+            }
+            HdrError::Malformed { expected, found } => {
+                write!(f, "Expected {expected}, found {found:?}")
+            }
+            HdrError::Truncated { expected, found } => {
+                write!(f, "Image data ended after {found} of {expected} pixels")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HdrError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HdrError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for HdrError {
+    fn from(e: std::io::Error) -> Self {
+        HdrError::Io(e)
+    }
+}
+
+// Returns the header line starting at `pos` without its line ending, and the position of
+// the line after it.
+fn header_line(data: &[u8], pos: usize) -> Option<(&str, usize)> {
+    let end = pos + data.get(pos..)?.iter().position(|b| *b == b'\n')?;
+    let line = std::str::from_utf8(&data[pos..end]).ok()?;
+    Some((line.trim_end_matches('\r'), end + 1))
+}
+
+fn malformed(expected: &'static str, found: &str) -> HdrError {
+    HdrError::Malformed {
+        expected,
+        found: found.to_string(),
+    }
+}
+
+/// Reads and writes Portable Float Map images, which store every channel as a 32-bit float.
+pub struct Pfm;
+
+impl Pfm {
+    /// # Errors
+    ///
+    /// Will return an error if the file indicated by `filepath` cannot be opened or
+    /// written to.
+    pub fn dump_to_file(canvas: &Canvas, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = BufWriter::new(File::create(filepath)?);
+        Pfm::write(canvas, &mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Writes the canvas as a little-endian color PFM image. Rows are stored from the
+    /// bottom of the image up, as the format requires.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing to `out` fails
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn write<W: Write>(canvas: &Canvas, mut out: W) -> io::Result<()> {
+        write!(out, "PF\n{} {}\n-1.0\n", canvas.width(), canvas.height())?;
+        let mut row_data = Vec::with_capacity(canvas.width() * 12);
+        for y in (0..canvas.height()).rev() {
+            row_data.clear();
+            for x in 0..canvas.width() {
+                let pixel = canvas.pixel_at(x, y);
+                for channel in [pixel.red, pixel.green, pixel.blue] {
+                    row_data.extend_from_slice(&(channel as f32).to_le_bytes());
+                }
+            }
+            out.write_all(&row_data)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a color (`PF`) or greyscale (`Pf`) PFM image. The sign of the scale in the
+    /// header gives the byte order of the data; its magnitude is ignored.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the data is not a PFM image, its header is malformed, or the
+    /// pixel data ends early.
+    pub fn parse(data: &[u8]) -> Result<Canvas, HdrError> {
+        // The header is three whitespace separated values after the magic number, followed
+        // by a single whitespace byte before the binary data.
+        let mut fields = Vec::with_capacity(4);
+        let mut pos = 0;
+        while fields.len() < 4 {
+            while data.get(pos).is_some_and(u8::is_ascii_whitespace) {
+                pos += 1;
+            }
+            let start = pos;
+            while data.get(pos).is_some_and(|b| !b.is_ascii_whitespace()) {
+                pos += 1;
+            }
+            if start == pos {
+                break;
+            }
+            fields.push(String::from_utf8_lossy(&data[start..pos]).into_owned());
+        }
+        let channels = match fields.first().map(String::as_str) {
+            Some("PF") => 3,
+            Some("Pf") => 1,
+            other => return Err(HdrError::UnsupportedFormat(other.unwrap_or("").to_string())),
+        };
+        let field = |i: usize| fields.get(i).map_or("end of file", String::as_str);
+        let width: usize = field(1)
+            .parse()
+            .map_err(|_| malformed("an image width", field(1)))?;
+        let height: usize = field(2)
+            .parse()
+            .map_err(|_| malformed("an image height", field(2)))?;
+        let scale: f32 = field(3)
+            .parse()
+            .map_err(|_| malformed("a scale and byte order", field(3)))?;
+        if scale == 0.0 || !scale.is_finite() {
+            return Err(malformed("a non-zero scale and byte order", field(3)));
+        }
+
+        let pixels = width
+            .checked_mul(height)
+            .ok_or_else(|| malformed("an image small enough to fit in memory", field(1)))?;
+        let pixel_data = data.get(pos + 1..).unwrap_or_default();
+        let pixel_size = channels * 4;
+        if pixel_data.len() / pixel_size < pixels {
+            return Err(HdrError::Truncated {
+                expected: pixels,
+                found: pixel_data.len() / pixel_size,
+            });
+        }
+
+        let value = |bytes: &[u8]| {
+            let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+            f64::from(if scale < 0.0 {
+                f32::from_le_bytes(bytes)
+            } else {
+                f32::from_be_bytes(bytes)
+            })
+        };
+        let mut canvas = Canvas::new(width, height);
+        for (i, pixel) in pixel_data.chunks_exact(pixel_size).take(pixels).enumerate() {
+            let color = if channels == 3 {
+                Color::new(
+                    value(&pixel[0..4]),
+                    value(&pixel[4..8]),
+                    value(&pixel[8..12]),
+                )
+            } else {
+                let v = value(pixel);
+                Color::new(v, v, v)
+            };
+            canvas.write_pixel(i % width, height - 1 - i / width, color);
+        }
+        Ok(canvas)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the file cannot be read or does not hold a valid PFM image.
+    pub fn from_file(filepath: &str) -> Result<Canvas, HdrError> {
+        Pfm::parse(&fs::read(filepath)?)
+    }
+}
+
+/// Reads and writes Radiance `.hdr` images, which share one 8-bit exponent between the
+/// three 8-bit mantissas of each pixel.
+pub struct RadianceHdr;
+
+// Scanlines between these widths are run length encoded a component at a time.
+const MIN_RLE_WIDTH: usize = 8;
+const MAX_RLE_WIDTH: usize = 0x7fff;
+
+impl RadianceHdr {
+    /// # Errors
+    ///
+    /// Will return an error if the file indicated by `filepath` cannot be opened or
+    /// written to.
+    pub fn dump_to_file(canvas: &Canvas, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = BufWriter::new(File::create(filepath)?);
+        RadianceHdr::write(canvas, &mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Writes the canvas as a run length encoded RGBE image. Negative channels are stored
+    /// as zero, as the format cannot represent them.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing to `out` fails
+    pub fn write<W: Write>(canvas: &Canvas, mut out: W) -> io::Result<()> {
+        write!(
+            out,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            canvas.height(),
+            canvas.width()
+        )?;
+        let width = canvas.width();
+        let mut pixels = Vec::with_capacity(width * 4);
+        let mut row_data = Vec::with_capacity(width * 4 + 4);
+        for y in 0..canvas.height() {
+            pixels.clear();
+            for x in 0..width {
+                pixels.extend_from_slice(&to_rgbe(canvas.pixel_at(x, y)));
+            }
+            row_data.clear();
+            if (MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&width) {
+                row_data.extend_from_slice(&[2, 2]);
+                row_data.extend_from_slice(&u16::try_from(width).unwrap_or(0).to_be_bytes());
+                for component in 0..4 {
+                    let values: Vec<u8> =
+                        pixels.iter().skip(component).step_by(4).copied().collect();
+                    encode_runs(&values, &mut row_data);
+                }
+            } else {
+                row_data.extend_from_slice(&pixels);
+            }
+            out.write_all(&row_data)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a Radiance RGBE image stored top to bottom and left to right, the orientation
+    /// every common tool writes, with either flat or run length encoded scanlines.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the data is not an RGBE image, its header is malformed, or
+    /// the pixel data ends early.
+    pub fn parse(data: &[u8]) -> Result<Canvas, HdrError> {
+        let (magic, mut pos) = header_line(data, 0).unwrap_or_default();
+        if magic != "#?RADIANCE" && magic != "#?RGBE" {
+            return Err(HdrError::UnsupportedFormat(magic.to_string()));
+        }
+        loop {
+            let (line, next) =
+                header_line(data, pos).ok_or_else(|| malformed("a header line", "end of file"))?;
+            pos = next;
+            if line.is_empty() {
+                break;
+            }
+            if let Some(format) = line.strip_prefix("FORMAT=") {
+                if format != "32-bit_rle_rgbe" {
+                    return Err(HdrError::UnsupportedFormat(format.to_string()));
+                }
+            }
+        }
+
+        let (resolution, next) = header_line(data, pos)
+            .ok_or_else(|| malformed("an image resolution", "end of file"))?;
+        pos = next;
+        let parts: Vec<&str> = resolution.split_whitespace().collect();
+        let (height, width): (Option<usize>, Option<usize>) = match parts[..] {
+            ["-Y", height, "+X", width] => (height.parse().ok(), width.parse().ok()),
+            _ => (None, None),
+        };
+        let (Some(height), Some(width)) = (height, width) else {
+            return Err(malformed(
+                "a resolution of the form -Y <height> +X <width>",
+                resolution,
+            ));
+        };
+
+        let (Some(pixels), Some(scanline_size)) =
+            (width.checked_mul(height), min_scanline_size(width))
+        else {
+            return Err(malformed(
+                "an image small enough to fit in memory",
+                resolution,
+            ));
+        };
+        // Images without pixels have no scanline data at all.
+        if pixels == 0 {
+            return Ok(Canvas::new(width, height));
+        }
+        // Check the data could hold every scanline before allocating the image.
+        let rows = (data.len() - pos) / scanline_size;
+        if rows < height {
+            return Err(HdrError::Truncated {
+                expected: pixels,
+                found: rows * width,
+            });
+        }
+
+        let mut canvas = Canvas::new(width, height);
+        let mut row = vec![0u8; width * 4];
+        for y in 0..height {
+            pos = read_scanline(data, pos, &mut row).map_err(|e| match e {
+                ScanlineError::Truncated => HdrError::Truncated {
+                    expected: pixels,
+                    found: width * y,
+                },
+                ScanlineError::RunTooLong => malformed(
+                    "runs that fit within the scanline",
+                    &format!("scanline {y}"),
+                ),
+            })?;
+            for (x, rgbe) in row.chunks_exact(4).enumerate() {
+                canvas.write_pixel(x, y, from_rgbe(rgbe));
+            }
+        }
+        Ok(canvas)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the file cannot be read or does not hold a valid RGBE image.
+    pub fn from_file(filepath: &str) -> Result<Canvas, HdrError> {
+        RadianceHdr::parse(&fs::read(filepath)?)
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn to_rgbe(color: Color) -> [u8; 4] {
+    let (r, g, b) = (
+        color.red.max(0.0),
+        color.green.max(0.0),
+        color.blue.max(0.0),
+    );
+    let max = r.max(g).max(b);
+    if max < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    // Choose the exponent so that the largest channel's mantissa lies in [128, 256).
+    let mut exponent = max.log2().floor() as i32 + 1;
+    if max / 2f64.powi(exponent) >= 1.0 {
+        exponent += 1;
+    }
+    let exponent = exponent.min(127);
+    let scale = 256.0 / 2f64.powi(exponent);
+    let mantissa = |c: f64| (c * scale).min(255.0) as u8;
+    [
+        mantissa(r),
+        mantissa(g),
+        mantissa(b),
+        (exponent + 128) as u8,
+    ]
+}
+
+fn from_rgbe(rgbe: &[u8]) -> Color {
+    if rgbe[3] == 0 {
+        return Color::new(0.0, 0.0, 0.0);
+    }
+    let f = 2f64.powi(i32::from(rgbe[3]) - (128 + 8));
+    let channel = |c: u8| (f64::from(c) + 0.5) * f;
+    Color::new(channel(rgbe[0]), channel(rgbe[1]), channel(rgbe[2]))
+}
+
+// Encodes one component of a scanline as a sequence of runs, each either a repeated value
+// (a count above 128 followed by the value) or up to 128 literal values.
+#[allow(clippy::cast_possible_truncation)]
+fn encode_runs(values: &[u8], out: &mut Vec<u8>) {
+    let mut pos = 0;
+    while pos < values.len() {
+        let run = values[pos..]
+            .iter()
+            .take(127)
+            .take_while(|v| **v == values[pos])
+            .count();
+        if run >= 3 {
+            out.extend_from_slice(&[128 + run as u8, values[pos]]);
+            pos += run;
+            continue;
+        }
+        // Gather literals until the next run worth encoding starts.
+        let start = pos;
+        while pos < values.len() && pos - start < 128 {
+            let upcoming = values[pos..].iter().take(3);
+            if upcoming.len() == 3 && values[pos..pos + 3].iter().all(|v| *v == values[pos]) {
+                break;
+            }
+            pos += 1;
+        }
+        out.push((pos - start) as u8);
+        out.extend_from_slice(&values[start..pos]);
+    }
+}
+
+// The fewest bytes a scanline of `width` pixels can take up, which for an encoded scanline
+// is when every component is made of the longest possible runs.
+fn min_scanline_size(width: usize) -> Option<usize> {
+    if (MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&width) {
+        Some(4 + 4 * 2 * width.div_ceil(127))
+    } else {
+        width.checked_mul(4)
+    }
+}
+
+enum ScanlineError {
+    Truncated,
+    RunTooLong,
+}
+
+// Reads one scanline into `row`, returning the position after it.
+fn read_scanline(data: &[u8], pos: usize, row: &mut [u8]) -> Result<usize, ScanlineError> {
+    let width = row.len() / 4;
+    let bytes =
+        |start: usize, len: usize| data.get(start..start + len).ok_or(ScanlineError::Truncated);
+    let header = bytes(pos, 4)?;
+    let encoded = (MIN_RLE_WIDTH..=MAX_RLE_WIDTH).contains(&width)
+        && header[0] == 2
+        && header[1] == 2
+        && usize::from(u16::from_be_bytes([header[2], header[3]])) == width;
+    if !encoded {
+        row.copy_from_slice(bytes(pos, width * 4)?);
+        return Ok(pos + width * 4);
+    }
+
+    let mut pos = pos + 4;
+    for component in 0..4 {
+        let mut x = 0;
+        while x < width {
+            let count = bytes(pos, 1)?[0];
+            pos += 1;
+            if count > 128 {
+                let count = usize::from(count - 128);
+                let value = bytes(pos, 1)?[0];
+                pos += 1;
+                if x + count > width {
+                    return Err(ScanlineError::RunTooLong);
+                }
+                for i in x..x + count {
+                    row[i * 4 + component] = value;
+                }
+                x += count;
+            } else {
+                let count = usize::from(count);
+                if count == 0 || x + count > width {
+                    return Err(ScanlineError::RunTooLong);
+                }
+                for (i, value) in bytes(pos, count)?.iter().enumerate() {
+                    row[(x + i) * 4 + component] = *value;
+                }
+                pos += count;
+                x += count;
+            }
+        }
+    }
+    Ok(pos)
+}
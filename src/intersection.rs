@@ -8,6 +8,8 @@ use crate::EPSILON;
 pub struct Intersection {
     pub t: f64,
     pub object_id: usize,
+    pub u: f64,
+    pub v: f64,
 }
 
 #[derive(Debug)]
@@ -27,7 +29,19 @@ pub struct Comp {
 impl Intersection {
     #[must_use]
     pub fn new(t: f64, object_id: usize) -> Self {
-        Intersection { t, object_id }
+        Intersection {
+            t,
+            object_id,
+            u: 0.0,
+            v: 0.0,
+        }
+    }
+
+    /// Creates an intersection that records where on a triangle the hit occurred, as
+    /// barycentric coordinates relative to its second and third vertices.
+    #[must_use]
+    pub fn with_uv(t: f64, object_id: usize, u: f64, v: f64) -> Self {
+        Intersection { t, object_id, u, v }
     }
 
     #[must_use]
@@ -39,7 +53,7 @@ impl Intersection {
         let object = world.get_object(self.object_id).unwrap();
         let point = r.position(t);
         let eyev = -r.direction;
-        let mut normalv = object.normal_at(point);
+        let mut normalv = object.normal_at_hit(point, self);
         let mut inside = false;
         if normalv.dot_product(&eyev) < 0.0 {
             inside = true;
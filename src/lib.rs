@@ -1,16 +1,30 @@
 #![warn(clippy::pedantic)]
 
+pub mod area_light;
+pub mod bounds;
+pub mod bvh;
 pub mod camera;
 pub mod canvas;
 pub mod color;
+mod deflate;
+pub mod directional_light;
+pub mod hdr_image;
 pub mod intersection;
+pub mod light;
 pub mod material;
 pub mod matrix;
+pub mod obj_parser;
 pub mod pattern;
+pub mod png_encoder;
 pub mod point_light;
+pub mod ppm_parser;
 pub mod ppm_printer;
+pub mod progress;
 pub mod ray;
+pub mod sampling;
 pub mod shape;
+pub mod spot_light;
+pub mod tone_map;
 pub mod transform;
 pub mod tuple;
 pub mod world;
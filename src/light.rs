@@ -0,0 +1,153 @@
+use crate::area_light::AreaLight;
+use crate::color::Color;
+use crate::directional_light::DirectionalLight;
+use crate::point_light::PointLight;
+use crate::spot_light::SpotLight;
+use crate::tuple::Tuple;
+
+/// Any of the lights a world can hold. Shading and shadows only go through the methods
+/// here, so every kind of light is treated the same way by `World` and `Material`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Light {
+    Point(PointLight),
+    Area(AreaLight),
+    Spot(SpotLight),
+    Directional(DirectionalLight),
+}
+
+/// The way towards one point on a light, as seen from the point being shaded.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LightSample {
+    /// A unit vector pointing towards the light.
+    pub direction: Tuple,
+    /// How far away the light is along `direction`, which is infinite for directional
+    /// lights.
+    pub distance: f64,
+}
+
+impl LightSample {
+    fn towards(from: Tuple, point: Tuple) -> Self {
+        let v = point - from;
+        LightSample {
+            direction: v.normalize(),
+            distance: v.magnitude(),
+        }
+    }
+}
+
+impl Light {
+    #[must_use]
+    pub fn id(&self) -> usize {
+        match self {
+            Light::Point(l) => l.id,
+            Light::Area(l) => l.id,
+            Light::Spot(l) => l.id,
+            Light::Directional(l) => l.id,
+        }
+    }
+
+    pub fn set_id(&mut self, id: usize) {
+        match self {
+            Light::Point(l) => l.id = id,
+            Light::Area(l) => l.id = id,
+            Light::Spot(l) => l.id = id,
+            Light::Directional(l) => l.id = id,
+        }
+    }
+
+    /// The color and brightness of the light where it is emitted.
+    #[must_use]
+    pub fn intensity(&self) -> Color {
+        match self {
+            Light::Point(l) => l.intensity,
+            Light::Area(l) => l.intensity,
+            Light::Spot(l) => l.intensity,
+            Light::Directional(l) => l.intensity,
+        }
+    }
+
+    /// The intensity of the light arriving at `point`, ignoring anything in the way. This
+    /// includes the attenuation of point lights and the falloff of spot lights.
+    #[must_use]
+    pub fn intensity_at(&self, point: Tuple) -> Color {
+        match self {
+            Light::Point(l) => l.intensity_at(point),
+            Light::Spot(l) => l.intensity * l.falloff(point),
+            _ => self.intensity(),
+        }
+    }
+
+    /// Returns the points on the light that shading and shadows are computed against, as
+    /// seen from `from`. Area lights give one sample per cell and other lights a single
+    /// one.
+    #[must_use]
+    pub fn samples(&self, from: Tuple) -> Vec<LightSample> {
+        match self {
+            Light::Area(l) => l
+                .sample_points(from)
+                .into_iter()
+                .map(|point| LightSample::towards(from, point))
+                .collect(),
+            _ => vec![LightSample {
+                direction: self.direction_from(from),
+                distance: self.distance_from(from),
+            }],
+        }
+    }
+
+    /// Returns the position of the light, or the centre of an area light. Directional
+    /// lights have no position.
+    #[must_use]
+    pub fn position(&self) -> Option<Tuple> {
+        match self {
+            Light::Point(PointLight { position, .. })
+            | Light::Area(AreaLight { position, .. })
+            | Light::Spot(SpotLight { position, .. }) => Some(*position),
+            Light::Directional(_) => None,
+        }
+    }
+
+    /// Returns the unit vector from `from` towards the light, or towards the centre of an
+    /// area light.
+    #[must_use]
+    pub fn direction_from(&self, from: Tuple) -> Tuple {
+        match self {
+            Light::Point(PointLight { position, .. })
+            | Light::Area(AreaLight { position, .. })
+            | Light::Spot(SpotLight { position, .. }) => (*position - from).normalize(),
+            Light::Directional(l) => l.lightv(),
+        }
+    }
+
+    /// Returns the distance from `from` to the light, which is infinite for directional
+    /// lights.
+    #[must_use]
+    pub fn distance_from(&self, from: Tuple) -> f64 {
+        self.position()
+            .map_or(f64::INFINITY, |position| (position - from).magnitude())
+    }
+}
+
+impl From<PointLight> for Light {
+    fn from(light: PointLight) -> Self {
+        Light::Point(light)
+    }
+}
+
+impl From<AreaLight> for Light {
+    fn from(light: AreaLight) -> Self {
+        Light::Area(light)
+    }
+}
+
+impl From<SpotLight> for Light {
+    fn from(light: SpotLight) -> Self {
+        Light::Spot(light)
+    }
+}
+
+impl From<DirectionalLight> for Light {
+    fn from(light: DirectionalLight) -> Self {
+        Light::Directional(light)
+    }
+}
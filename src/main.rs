@@ -4,13 +4,15 @@ use ray_tracer::camera::Camera;
 use ray_tracer::color::Color;
 use ray_tracer::pattern::Pattern;
 use ray_tracer::point_light::PointLight;
-use ray_tracer::ppm_printer::PpmPrinter;
+use ray_tracer::progress::{CancellationToken, ProgressBar};
 use ray_tracer::shape::Shape;
 use ray_tracer::transform::Transform;
 use ray_tracer::tuple::Tuple;
 use ray_tracer::world::World;
 
 use std::f64::consts::PI;
+use std::num::NonZeroUsize;
+use std::thread;
 
 fn main() {
     let mut floor = Shape::plane();
@@ -44,18 +46,28 @@ fn main() {
         Color::new(1.0, 1.0, 1.0),
     ));
 
-    world.add_object(floor);
-    world.add_object(big_ball);
-    world.add_object(small_ball);
+    world.add_object(floor).unwrap();
+    world.add_object(big_ball).unwrap();
+    world.add_object(small_ball).unwrap();
 
     let mut camera = Camera::new(200.0, 100.0, PI / 3.0);
-    camera.transform = Transform::view_transform(
-        &Tuple::point(0.0, 1.5, -8.0),
-        &Tuple::point(0.0, 1.0, 0.0),
-        &Tuple::vector(0.0, 1.0, 0.0),
-    );
+    camera
+        .set_transform(Transform::view_transform(
+            &Tuple::point(0.0, 1.5, -8.0),
+            &Tuple::point(0.0, 1.0, 0.0),
+            &Tuple::vector(0.0, 1.0, 0.0),
+        ))
+        .unwrap();
 
-    let image = camera.render(&world);
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let image = camera
+        .render_observed(
+            &world,
+            threads,
+            &mut ProgressBar::stdout(),
+            &CancellationToken::new(),
+        )
+        .unwrap();
 
-    PpmPrinter::dump_to_file(&image, "no_shadow.ppm").unwrap();
+    image.save("no_shadow.png").unwrap();
 }
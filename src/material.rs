@@ -1,6 +1,6 @@
 use crate::color::Color;
+use crate::light::Light;
 use crate::pattern::Pattern;
-use crate::point_light::PointLight;
 use crate::shape::Shape;
 use crate::tuple::Tuple;
 
@@ -43,34 +43,64 @@ impl Material {
         self.pattern = Some(p.clone());
     }
 
+    /// Shades `position` as lit by `light`. `light_intensity` is the fraction of the light
+    /// that reaches the point, from 0 when it is in shadow to 1 when nothing is in the way,
+    /// and scales the diffuse and specular contributions. Those are averaged over the
+    /// light's samples, so area lights light a point from across their whole surface, and
+    /// use the light's intensity at `position`, so they are attenuated by distance and spot
+    /// light cones while the ambient contribution is not.
     #[must_use]
+    #[allow(clippy::cast_precision_loss)]
     pub fn lighting(
         &self,
-        light: &PointLight,
+        light: &Light,
         object: &Shape,
         position: Tuple,
         eye: Tuple,
         normal: Tuple,
-        in_shadow: bool,
+        light_intensity: f64,
     ) -> Color {
-        let mut effective_color = self.color * light.intensity;
-        if let Some(pattern) = &self.pattern {
-            effective_color = pattern.color_at_object(object, position) * light.intensity;
+        let color = self.color_at(object, position);
+        let ambient = color * light.intensity() * self.ambient;
+        let intensity = light.intensity_at(position);
+        let samples = light.samples(position);
+        let mut direct = Color::new(0.0, 0.0, 0.0);
+        for sample in &samples {
+            direct +=
+                self.direct_lighting(color * intensity, intensity, sample.direction, eye, normal);
         }
-        let lightv = (light.position - position).normalize();
-        let ambient = effective_color * self.ambient;
+        ambient + direct * (light_intensity / samples.len() as f64)
+    }
+
+    fn color_at(&self, object: &Shape, position: Tuple) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.color_at_object(object, position),
+            None => self.color,
+        }
+    }
+
+    // The diffuse and specular light arriving along `lightv`, the direction from the point
+    // being shaded towards the light.
+    fn direct_lighting(
+        &self,
+        effective_color: Color,
+        intensity: Color,
+        lightv: Tuple,
+        eye: Tuple,
+        normal: Tuple,
+    ) -> Color {
         let light_dot_normal = lightv.dot_product(&normal);
-        let mut diffuse = Color::new(0.0, 0.0, 0.0);
-        let mut specular = Color::new(0.0, 0.0, 0.0);
-        if light_dot_normal >= 0.0 && !in_shadow {
-            diffuse = effective_color * self.diffuse * light_dot_normal;
-            let reflect = (-lightv).reflect(&normal);
-            let reflect_dot_eye = reflect.dot_product(&eye);
-            if reflect_dot_eye > 0.0 {
-                let factor = f64::powf(reflect_dot_eye, self.shininess);
-                specular = light.intensity * self.specular * factor;
-            }
+        if light_dot_normal < 0.0 {
+            return Color::new(0.0, 0.0, 0.0);
+        }
+        let diffuse = effective_color * self.diffuse * light_dot_normal;
+        let reflect = (-lightv).reflect(&normal);
+        let reflect_dot_eye = reflect.dot_product(&eye);
+        if reflect_dot_eye > 0.0 {
+            let factor = f64::powf(reflect_dot_eye, self.shininess);
+            diffuse + intensity * self.specular * factor
+        } else {
+            diffuse
         }
-        ambient + diffuse + specular
     }
 }
@@ -1,6 +1,20 @@
 use crate::float_near_equal;
 use crate::tuple::Tuple;
 
+use std::fmt;
+
+/// Returned when inverting a matrix whose determinant is zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SingularMatrix;
+
+impl fmt::Display for SingularMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Matrix is not invertible")
+    }
+}
+
+impl std::error::Error for SingularMatrix {}
+
 #[derive(Debug, Clone)]
 pub struct Matrix {
     buffer: Vec<Vec<f64>>,
@@ -90,7 +104,7 @@ impl Matrix {
     #[must_use]
     pub fn cofactor(&self, row: usize, col: usize) -> f64 {
         let m = self.minor(row, col);
-        if (row + col) % 2 == 0 {
+        if (row + col).is_multiple_of(2) {
             m
         } else {
             -m
@@ -107,9 +121,17 @@ impl Matrix {
     ///
     /// Will panic if self is not invertible
     pub fn inverse(&self) -> Matrix {
-        let d = self.determinant();
+        self.try_inverse().expect("matrix is not invertible")
+    }
 
-        assert!(d != 0.0);
+    /// # Errors
+    ///
+    /// Will return `SingularMatrix` if the determinant of self is zero
+    pub fn try_inverse(&self) -> Result<Matrix, SingularMatrix> {
+        let d = self.determinant();
+        if d == 0.0 {
+            return Err(SingularMatrix);
+        }
         let mut result = self.buffer.clone();
 
         for (y, row) in self.buffer.iter().enumerate() {
@@ -118,7 +140,7 @@ impl Matrix {
                 result[x][y] = c / d;
             }
         }
-        Matrix::new(result)
+        Ok(Matrix::new(result))
     }
 
     #[must_use]
@@ -182,3 +204,167 @@ impl PartialEq for Matrix {
         true
     }
 }
+
+/// A 4x4 matrix stored row by row in a flat array, so it can be copied freely and none of
+/// its operations allocate.
+#[derive(Clone, Copy, Debug)]
+pub struct Matrix4 {
+    data: [f64; 16],
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::identity_matrix()
+    }
+}
+
+impl Matrix4 {
+    #[must_use]
+    pub fn new(rows: [[f64; 4]; 4]) -> Self {
+        let mut data = [0.0; 16];
+        for (row, values) in rows.iter().enumerate() {
+            data[row * 4..row * 4 + 4].copy_from_slice(values);
+        }
+        Matrix4 { data }
+    }
+
+    #[must_use]
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        self.data[row * 4 + col]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, val: f64) {
+        self.data[row * 4 + col] = val;
+    }
+
+    #[must_use]
+    pub fn identity_matrix() -> Matrix4 {
+        Matrix4::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    #[must_use]
+    pub fn transpose(&self) -> Matrix4 {
+        let mut data = [0.0; 16];
+        for row in 0..4 {
+            for col in 0..4 {
+                data[col * 4 + row] = self.data[row * 4 + col];
+            }
+        }
+        Matrix4 { data }
+    }
+
+    // The determinants of the 2x2 blocks taken from the top two rows and from the bottom
+    // two rows, which both the determinant and the inverse are expanded in terms of.
+    fn sub_determinants(&self) -> ([f64; 6], [f64; 6]) {
+        let m = &self.data;
+        let s = [
+            m[0] * m[5] - m[4] * m[1],
+            m[0] * m[6] - m[4] * m[2],
+            m[0] * m[7] - m[4] * m[3],
+            m[1] * m[6] - m[5] * m[2],
+            m[1] * m[7] - m[5] * m[3],
+            m[2] * m[7] - m[6] * m[3],
+        ];
+        let c = [
+            m[8] * m[13] - m[12] * m[9],
+            m[8] * m[14] - m[12] * m[10],
+            m[8] * m[15] - m[12] * m[11],
+            m[9] * m[14] - m[13] * m[10],
+            m[9] * m[15] - m[13] * m[11],
+            m[10] * m[15] - m[14] * m[11],
+        ];
+        (s, c)
+    }
+
+    #[must_use]
+    pub fn determinant(&self) -> f64 {
+        let (s, c) = self.sub_determinants();
+        s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0]
+    }
+
+    #[must_use]
+    pub fn is_invertible(&self) -> bool {
+        self.determinant() != 0.0
+    }
+
+    #[must_use]
+    /// # Panics
+    ///
+    /// Will panic if self is not invertible
+    pub fn inverse(&self) -> Matrix4 {
+        self.try_inverse().expect("matrix is not invertible")
+    }
+
+    /// # Errors
+    ///
+    /// Will return `SingularMatrix` if the determinant of self is zero
+    pub fn try_inverse(&self) -> Result<Matrix4, SingularMatrix> {
+        let m = &self.data;
+        let (s, c) = self.sub_determinants();
+        let d = s[0] * c[5] - s[1] * c[4] + s[2] * c[3] + s[3] * c[2] - s[4] * c[1] + s[5] * c[0];
+        if d == 0.0 {
+            return Err(SingularMatrix);
+        }
+        let inv = 1.0 / d;
+        let adjugate = [
+            m[5] * c[5] - m[6] * c[4] + m[7] * c[3],
+            -m[1] * c[5] + m[2] * c[4] - m[3] * c[3],
+            m[13] * s[5] - m[14] * s[4] + m[15] * s[3],
+            -m[9] * s[5] + m[10] * s[4] - m[11] * s[3],
+            -m[4] * c[5] + m[6] * c[2] - m[7] * c[1],
+            m[0] * c[5] - m[2] * c[2] + m[3] * c[1],
+            -m[12] * s[5] + m[14] * s[2] - m[15] * s[1],
+            m[8] * s[5] - m[10] * s[2] + m[11] * s[1],
+            m[4] * c[4] - m[5] * c[2] + m[7] * c[0],
+            -m[0] * c[4] + m[1] * c[2] - m[3] * c[0],
+            m[12] * s[4] - m[13] * s[2] + m[15] * s[0],
+            -m[8] * s[4] + m[9] * s[2] - m[11] * s[0],
+            -m[4] * c[3] + m[5] * c[1] - m[6] * c[0],
+            m[0] * c[3] - m[1] * c[1] + m[2] * c[0],
+            -m[12] * s[3] + m[13] * s[1] - m[14] * s[0],
+            m[8] * s[3] - m[9] * s[1] + m[10] * s[0],
+        ];
+        Ok(Matrix4 {
+            data: adjugate.map(|v| v * inv),
+        })
+    }
+
+    #[must_use]
+    pub fn matrix_multiply(&self, rhs: &Matrix4) -> Matrix4 {
+        let mut data = [0.0; 16];
+        for row in 0..4 {
+            for col in 0..4 {
+                data[row * 4 + col] = self.data[row * 4] * rhs.data[col]
+                    + self.data[row * 4 + 1] * rhs.data[4 + col]
+                    + self.data[row * 4 + 2] * rhs.data[8 + col]
+                    + self.data[row * 4 + 3] * rhs.data[12 + col];
+            }
+        }
+        Matrix4 { data }
+    }
+
+    #[must_use]
+    pub fn tuple_multiply(&self, rhs: &Tuple) -> Tuple {
+        let m = &self.data;
+        Tuple::new(
+            m[0] * rhs.x + m[1] * rhs.y + m[2] * rhs.z + m[3] * rhs.w,
+            m[4] * rhs.x + m[5] * rhs.y + m[6] * rhs.z + m[7] * rhs.w,
+            m[8] * rhs.x + m[9] * rhs.y + m[10] * rhs.z + m[11] * rhs.w,
+            m[12] * rhs.x + m[13] * rhs.y + m[14] * rhs.z + m[15] * rhs.w,
+        )
+    }
+}
+
+impl PartialEq for Matrix4 {
+    fn eq(&self, other: &Self) -> bool {
+        self.data
+            .iter()
+            .zip(other.data.iter())
+            .all(|(a, b)| float_near_equal(*a, *b))
+    }
+}
@@ -0,0 +1,284 @@
+use crate::shape::Shape;
+use crate::tuple::Tuple;
+
+use std::fmt;
+use std::fs;
+
+#[derive(Debug)]
+pub enum ObjError {
+    Io(std::io::Error),
+    MalformedLine { line: usize, text: String },
+    InvalidIndex { line: usize, index: String },
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ObjError::Io(e) => write!(f, "Could not read OBJ file: {e}"),
+            ObjError::MalformedLine { line, text } => {
+                write!(f, "Malformed statement on line {line}: {text}")
+            }
+            ObjError::InvalidIndex { line, index } => {
+                write!(f, "Invalid index {index} on line {line}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ObjError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ObjError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ObjError {
+    fn from(e: std::io::Error) -> Self {
+        ObjError::Io(e)
+    }
+}
+
+/// A line the parser did not understand and skipped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IgnoredLine {
+    pub line: usize,
+    pub text: String,
+}
+
+// The indices of one corner of a face, already resolved to zero-based positions.
+#[derive(Clone, Copy)]
+struct FaceVertex {
+    vertex: usize,
+    normal: Option<usize>,
+}
+
+pub struct ObjParser {
+    vertices: Vec<Tuple>,
+    normals: Vec<Tuple>,
+    texture_coords: Vec<(f64, f64)>,
+    default_group: Shape,
+    groups: Vec<(String, Shape)>,
+    ignored: Vec<IgnoredLine>,
+}
+
+impl ObjParser {
+    /// Parses the contents of a Wavefront OBJ file. Faces with more than three vertices are
+    /// split into a fan of triangles, and faces whose vertices all carry normals become
+    /// smooth triangles. Faces following a `g` or `o` statement are collected into a group
+    /// of that name.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if a vertex, normal or face statement is malformed or a face
+    /// refers to an element that does not exist.
+    pub fn parse(input: &str) -> Result<Self, ObjError> {
+        let mut parser = ObjParser {
+            vertices: vec![],
+            normals: vec![],
+            texture_coords: vec![],
+            default_group: Shape::group(),
+            groups: vec![],
+            ignored: vec![],
+        };
+        let mut current_group: Option<usize> = None;
+
+        for (idx, raw_line) in input.lines().enumerate() {
+            let line = idx + 1;
+            let text = raw_line.trim();
+            if text.is_empty() || text.starts_with('#') {
+                continue;
+            }
+            let mut fields = text.split_whitespace();
+            let keyword = fields.next().unwrap_or_default();
+            let args: Vec<&str> = fields.collect();
+            let malformed = || ObjError::MalformedLine {
+                line,
+                text: text.to_string(),
+            };
+
+            match keyword {
+                "v" => {
+                    let [x, y, z, _w] = parse_floats(&args, 3).ok_or_else(malformed)?;
+                    parser.vertices.push(Tuple::point(x, y, z));
+                }
+                "vn" => {
+                    let [x, y, z] = parse_floats(&args, 3).ok_or_else(malformed)?;
+                    parser.normals.push(Tuple::vector(x, y, z));
+                }
+                "vt" => {
+                    let [u, v, _w] = parse_floats(&args, 1).ok_or_else(malformed)?;
+                    parser.texture_coords.push((u, v));
+                }
+                "f" => {
+                    if args.len() < 3 {
+                        return Err(malformed());
+                    }
+                    let corners = args
+                        .iter()
+                        .map(|arg| parser.face_vertex(arg, line))
+                        .collect::<Result<Vec<FaceVertex>, ObjError>>()?;
+                    for triangle in parser.fan_triangulation(&corners) {
+                        match current_group {
+                            Some(g) => parser.groups[g].1.add_child(triangle),
+                            None => parser.default_group.add_child(triangle),
+                        }
+                    }
+                }
+                "g" | "o" => {
+                    let name = args.join(" ");
+                    if name.is_empty() {
+                        return Err(malformed());
+                    }
+                    if let Some(g) = parser.groups.iter().position(|(n, _)| *n == name) {
+                        current_group = Some(g);
+                    } else {
+                        parser.groups.push((name, Shape::group()));
+                        current_group = Some(parser.groups.len() - 1);
+                    }
+                }
+                _ => parser.ignored.push(IgnoredLine {
+                    line,
+                    text: text.to_string(),
+                }),
+            }
+        }
+        Ok(parser)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the file indicated by `filepath` cannot be read or its
+    /// contents cannot be parsed.
+    pub fn from_file(filepath: &str) -> Result<Self, ObjError> {
+        let contents = fs::read_to_string(filepath)?;
+        ObjParser::parse(&contents)
+    }
+
+    /// Returns the vertex with the given one-based index, as numbered in the file.
+    #[must_use]
+    pub fn vertex(&self, index: usize) -> Option<Tuple> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.vertices.get(i))
+            .copied()
+    }
+
+    /// Returns the vertex normal with the given one-based index, as numbered in the file.
+    #[must_use]
+    pub fn normal(&self, index: usize) -> Option<Tuple> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.normals.get(i))
+            .copied()
+    }
+
+    /// Returns the texture coordinate with the given one-based index, as numbered in the file.
+    #[must_use]
+    pub fn texture_coord(&self, index: usize) -> Option<(f64, f64)> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.texture_coords.get(i))
+            .copied()
+    }
+
+    /// The group holding every face that appeared before the first named group.
+    #[must_use]
+    pub fn default_group(&self) -> &Shape {
+        &self.default_group
+    }
+
+    #[must_use]
+    pub fn group(&self, name: &str) -> Option<&Shape> {
+        self.groups.iter().find(|(n, _)| n == name).map(|(_, g)| g)
+    }
+
+    #[must_use]
+    pub fn ignored_lines(&self) -> &[IgnoredLine] {
+        &self.ignored
+    }
+
+    /// Combines every parsed face into a single group that can be added to a `World`. Named
+    /// groups become child groups of the result.
+    #[must_use]
+    pub fn into_group(self) -> Shape {
+        let mut result = self.default_group;
+        for (_, group) in self.groups {
+            result.add_child(group);
+        }
+        result
+    }
+
+    fn face_vertex(&self, arg: &str, line: usize) -> Result<FaceVertex, ObjError> {
+        let mut parts = arg.split('/');
+        let vertex = resolve_index(parts.next().unwrap_or_default(), self.vertices.len(), line)?;
+        if let Some(texture) = parts.next().filter(|t| !t.is_empty()) {
+            resolve_index(texture, self.texture_coords.len(), line)?;
+        }
+        let normal = match parts.next().filter(|n| !n.is_empty()) {
+            Some(n) => Some(resolve_index(n, self.normals.len(), line)?),
+            None => None,
+        };
+        if parts.next().is_some() {
+            return Err(ObjError::InvalidIndex {
+                line,
+                index: arg.to_string(),
+            });
+        }
+        Ok(FaceVertex { vertex, normal })
+    }
+
+    fn fan_triangulation(&self, corners: &[FaceVertex]) -> Vec<Shape> {
+        let first = corners[0];
+        corners[1..]
+            .windows(2)
+            .map(|pair| {
+                let (second, third) = (pair[0], pair[1]);
+                let p1 = self.vertices[first.vertex];
+                let p2 = self.vertices[second.vertex];
+                let p3 = self.vertices[third.vertex];
+                match (first.normal, second.normal, third.normal) {
+                    (Some(n1), Some(n2), Some(n3)) => Shape::smooth_triangle(
+                        p1,
+                        p2,
+                        p3,
+                        self.normals[n1],
+                        self.normals[n2],
+                        self.normals[n3],
+                    ),
+                    _ => Shape::triangle(p1, p2, p3),
+                }
+            })
+            .collect()
+    }
+}
+
+// Parses between `required` and N numbers, filling in any that were left out with zero.
+// Vertices take an optional weight and texture coordinates an optional `v` and `w`.
+fn parse_floats<const N: usize>(args: &[&str], required: usize) -> Option<[f64; N]> {
+    if args.len() < required || args.len() > N {
+        return None;
+    }
+    let mut result = [0.0; N];
+    for (slot, arg) in result.iter_mut().zip(args) {
+        *slot = arg.parse().ok()?;
+    }
+    Some(result)
+}
+
+// Converts a one-based (or negative, counting back from the most recent element) OBJ index
+// into a position in a list of `count` elements.
+fn resolve_index(raw: &str, count: usize, line: usize) -> Result<usize, ObjError> {
+    let invalid = || ObjError::InvalidIndex {
+        line,
+        index: raw.to_string(),
+    };
+    let index: i64 = raw.parse().map_err(|_| invalid())?;
+    let count = i64::try_from(count).map_err(|_| invalid())?;
+    let resolved = if index > 0 { index - 1 } else { count + index };
+    if index == 0 || resolved < 0 || resolved >= count {
+        return Err(invalid());
+    }
+    usize::try_from(resolved).map_err(|_| invalid())
+}
@@ -20,6 +20,8 @@ pub struct Pattern {
     b: Color,
     kind: PatternType,
     transform: Transform,
+    // `None` if `transform` cannot be inverted.
+    inverse: Option<Transform>,
 }
 
 impl Pattern {
@@ -30,6 +32,7 @@ impl Pattern {
             b: Color::new(0.0, 0.0, 0.0),
             kind: PatternType::Test,
             transform: Transform::new(),
+            inverse: Some(Transform::new()),
         }
     }
     #[must_use]
@@ -39,6 +42,7 @@ impl Pattern {
             b,
             kind: PatternType::Stripes,
             transform: Transform::new(),
+            inverse: Some(Transform::new()),
         }
     }
 
@@ -49,6 +53,7 @@ impl Pattern {
             b,
             kind: PatternType::Gradient,
             transform: Transform::new(),
+            inverse: Some(Transform::new()),
         }
     }
 
@@ -59,6 +64,7 @@ impl Pattern {
             b,
             kind: PatternType::Rings,
             transform: Transform::new(),
+            inverse: Some(Transform::new()),
         }
     }
 
@@ -69,6 +75,7 @@ impl Pattern {
             b,
             kind: PatternType::Checkered,
             transform: Transform::new(),
+            inverse: Some(Transform::new()),
         }
     }
 
@@ -124,37 +131,56 @@ impl Pattern {
         }
     }
 
+    /// # Panics
+    ///
+    /// Will panic if the transform of the pattern or of `object` cannot be inverted
     #[must_use]
     pub fn color_at_object(&self, object: &Shape, pos: Tuple) -> Color {
-        let obj_transform = object.transform().clone().inverse();
-        let object_point = obj_transform.transform(&pos);
-        let pattern_transform = self.transform.clone().inverse();
-        let pattern_point = pattern_transform.transform(&object_point);
+        let inverse = self
+            .inverse
+            .expect("the transform of this pattern cannot be inverted");
+        let object_point = object.world_to_object(pos);
+        let pattern_point = inverse.transform(&object_point);
 
         self.color_at(pattern_point)
     }
 
+    #[must_use]
+    pub fn transform(&self) -> &Transform {
+        &self.transform
+    }
+
+    fn transform_changed(&mut self) {
+        self.inverse = self.transform.try_inverse().ok();
+    }
+
     pub fn scale(&mut self, x: f64, y: f64, z: f64) {
-        self.transform = self.transform.clone().scale(x, y, z);
+        self.transform = self.transform.scale(x, y, z);
+        self.transform_changed();
     }
 
     pub fn translate(&mut self, x: f64, y: f64, z: f64) {
-        self.transform = self.transform.clone().translate(x, y, z);
+        self.transform = self.transform.translate(x, y, z);
+        self.transform_changed();
     }
 
     pub fn rotate_x(&mut self, r: f64) {
-        self.transform = self.transform.clone().rotate_x(r);
+        self.transform = self.transform.rotate_x(r);
+        self.transform_changed();
     }
 
     pub fn rotate_z(&mut self, r: f64) {
-        self.transform = self.transform.clone().rotate_z(r);
+        self.transform = self.transform.rotate_z(r);
+        self.transform_changed();
     }
 
     pub fn rotate_y(&mut self, r: f64) {
-        self.transform = self.transform.clone().rotate_y(r);
+        self.transform = self.transform.rotate_y(r);
+        self.transform_changed();
     }
 
     pub fn shear(&mut self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) {
-        self.transform = self.transform.clone().shear(xy, xz, yx, yz, zx, zy);
+        self.transform = self.transform.shear(xy, xz, yx, yz, zx, zy);
+        self.transform_changed();
     }
 }
@@ -0,0 +1,222 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::deflate::zlib_compress;
+use crate::tone_map::ToneMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// The number of bits used for each color channel of a PNG image.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PngBitDepth {
+    #[default]
+    Eight,
+    Sixteen,
+}
+
+impl PngBitDepth {
+    fn bits(self) -> u8 {
+        match self {
+            PngBitDepth::Eight => 8,
+            PngBitDepth::Sixteen => 16,
+        }
+    }
+
+    fn bytes_per_pixel(self) -> usize {
+        usize::from(self.bits()) * 3 / 8
+    }
+}
+
+pub struct PngEncoder;
+
+impl PngEncoder {
+    /// # Errors
+    ///
+    /// Will return an error if the file indicated by `filepath` cannot be opened or
+    /// written to, or if the canvas is empty.
+    pub fn dump_to_file(
+        canvas: &Canvas,
+        filepath: &str,
+        depth: PngBitDepth,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = BufWriter::new(File::create(filepath)?);
+        PngEncoder::write(canvas, depth, &mut file)?;
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Writes the canvas to `out` as an RGB PNG image. Colors are clamped to between 0 and 1
+    /// and are not gamma corrected.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing to `out` fails, or if the canvas has no pixels, which
+    /// PNG cannot represent.
+    pub fn write<W: Write>(canvas: &Canvas, depth: PngBitDepth, out: W) -> io::Result<()> {
+        PngEncoder::write_tone_mapped(canvas, depth, &ToneMap::default(), out)
+    }
+
+    /// Writes the canvas to `out` like `write`, passing every pixel through `tone_map` before
+    /// it is quantized.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing to `out` fails, or if the canvas has no pixels.
+    pub fn write_tone_mapped<W: Write>(
+        canvas: &Canvas,
+        depth: PngBitDepth,
+        tone_map: &ToneMap,
+        mut out: W,
+    ) -> io::Result<()> {
+        let (Ok(width), Ok(height)) = (
+            u32::try_from(canvas.width()),
+            u32::try_from(canvas.height()),
+        ) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "canvas is too large for a PNG image",
+            ));
+        };
+        if width == 0 || height == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "cannot write an empty canvas as a PNG image",
+            ));
+        }
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // Truecolor, with the default compression and filtering methods and no interlacing.
+        header.extend_from_slice(&[depth.bits(), 2, 0, 0, 0]);
+
+        out.write_all(&SIGNATURE)?;
+        write_chunk(&mut out, *b"IHDR", &header)?;
+        write_chunk(
+            &mut out,
+            *b"IDAT",
+            &zlib_compress(&PngEncoder::filtered_data(canvas, depth, tone_map)),
+        )?;
+        write_chunk(&mut out, *b"IEND", &[])
+    }
+
+    // Encodes every row of the canvas, preceded by the filter that makes it smallest by the
+    // usual heuristic of minimising the sum of the filtered bytes taken as signed values.
+    fn filtered_data(canvas: &Canvas, depth: PngBitDepth, tone_map: &ToneMap) -> Vec<u8> {
+        let bpp = depth.bytes_per_pixel();
+        let row_length = canvas.width() * bpp;
+        let mut data = Vec::with_capacity((row_length + 1) * canvas.height());
+        let mut previous = vec![0; row_length];
+        let mut current = Vec::with_capacity(row_length);
+        let mut candidate = vec![0; row_length];
+        let mut best = vec![0; row_length];
+
+        for y in 0..canvas.height() {
+            current.clear();
+            for x in 0..canvas.width() {
+                encode_pixel(
+                    tone_map.map_color(canvas.pixel_at(x, y)),
+                    depth,
+                    &mut current,
+                );
+            }
+
+            let mut best_filter = 0;
+            let mut best_cost = u64::MAX;
+            for filter in 0..5 {
+                apply_filter(filter, &current, &previous, bpp, &mut candidate);
+                let cost = candidate
+                    .iter()
+                    .map(|b| u64::from(b.cast_signed().unsigned_abs()))
+                    .sum();
+                if cost < best_cost {
+                    best_cost = cost;
+                    best_filter = filter;
+                    best.copy_from_slice(&candidate);
+                }
+            }
+            data.push(best_filter);
+            data.extend_from_slice(&best);
+            std::mem::swap(&mut previous, &mut current);
+        }
+        data
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn encode_pixel(color: Color, depth: PngBitDepth, out: &mut Vec<u8>) {
+    match depth {
+        PngBitDepth::Eight => {
+            out.extend_from_slice(&[
+                color.red_to_int(),
+                color.green_to_int(),
+                color.blue_to_int(),
+            ]);
+        }
+        PngBitDepth::Sixteen => {
+            for channel in [color.red, color.green, color.blue] {
+                let value = (channel * 65535.0).clamp(0.0, 65535.0).round() as u16;
+                out.extend_from_slice(&value.to_be_bytes());
+            }
+        }
+    }
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let (pa, pb, pc) = (
+        (p - i16::from(a)).abs(),
+        (p - i16::from(b)).abs(),
+        (p - i16::from(c)).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+// Applies one of the five PNG filter types to a row, given the unfiltered row above it.
+fn apply_filter(filter: u8, row: &[u8], above: &[u8], bpp: usize, out: &mut [u8]) {
+    for i in 0..row.len() {
+        let left = if i >= bpp { row[i - bpp] } else { 0 };
+        let upper_left = if i >= bpp { above[i - bpp] } else { 0 };
+        let predicted = match filter {
+            0 => 0,
+            1 => left,
+            2 => above[i],
+            3 => u8::midpoint(left, above[i]),
+            _ => paeth(left, above[i], upper_left),
+        };
+        out[i] = row[i].wrapping_sub(predicted);
+    }
+}
+
+fn write_chunk<W: Write>(out: &mut W, kind: [u8; 4], data: &[u8]) -> io::Result<()> {
+    let length = u32::try_from(data.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "PNG chunk is too large"))?;
+    out.write_all(&length.to_be_bytes())?;
+    out.write_all(&kind)?;
+    out.write_all(data)?;
+    let crc = crc32(&[&kind, data]);
+    out.write_all(&crc.to_be_bytes())
+}
+
+fn crc32(parts: &[&[u8]]) -> u32 {
+    let mut crc = u32::MAX;
+    for byte in parts.iter().flat_map(|part| part.iter()) {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
@@ -1,11 +1,67 @@
 use crate::color::Color;
 use crate::tuple::Tuple;
+use crate::EPSILON;
+
+/// How the light from a point light fades with the distance `d` from it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Attenuation {
+    /// The light is equally bright at any distance.
+    #[default]
+    None,
+    /// The light falls off physically, as `1 / d²`.
+    InverseSquare,
+    /// The light falls off as `1 / (constant + linear * d + quadratic * d²)`, which can be
+    /// tuned to look right without being too bright close to the light.
+    Polynomial {
+        constant: f64,
+        linear: f64,
+        quadratic: f64,
+    },
+}
+
+impl Attenuation {
+    /// Returns the factor the light's intensity is scaled by at distance `d`. The divisor
+    /// is never taken below `EPSILON`, so the factor stays finite at the light itself.
+    #[must_use]
+    pub fn factor(self, d: f64) -> f64 {
+        let divisor = match self {
+            Attenuation::None => return 1.0,
+            Attenuation::InverseSquare => d * d,
+            Attenuation::Polynomial {
+                constant,
+                linear,
+                quadratic,
+            } => constant + linear * d + quadratic * d * d,
+        };
+        1.0 / divisor.max(EPSILON)
+    }
+
+    // Polynomial coefficients must be finite and non-negative, and the constant or linear
+    // term must be positive so the divisor is positive away from the light.
+    fn is_valid(self) -> bool {
+        match self {
+            Attenuation::None | Attenuation::InverseSquare => true,
+            Attenuation::Polynomial {
+                constant,
+                linear,
+                quadratic,
+            } => {
+                [constant, linear, quadratic]
+                    .iter()
+                    .all(|c| c.is_finite() && *c >= 0.0)
+                    && (constant > 0.0 || linear > 0.0)
+            }
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct PointLight {
     pub position: Tuple,
     pub intensity: Color,
     pub id: usize,
+    attenuation: Attenuation,
+    cutoff: f64,
 }
 
 impl PointLight {
@@ -15,6 +71,55 @@ impl PointLight {
             position,
             intensity,
             id: 0,
+            attenuation: Attenuation::None,
+            cutoff: f64::INFINITY,
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if a polynomial model has a negative or non-finite coefficient, or has
+    /// neither a constant nor a linear term
+    pub fn set_attenuation(&mut self, attenuation: Attenuation) {
+        assert!(
+            attenuation.is_valid(),
+            "attenuation coefficients must be finite and non-negative, with a constant or linear term"
+        );
+        self.attenuation = attenuation;
+    }
+
+    #[must_use]
+    pub fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    /// Points further than `radius` from the light receive none of its light. Lights start
+    /// out without a cutoff, which is an infinite radius.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `radius` is negative or `NaN`
+    pub fn set_cutoff(&mut self, radius: f64) {
+        assert!(
+            radius >= 0.0,
+            "a point light's cutoff radius must be zero or more"
+        );
+        self.cutoff = radius;
+    }
+
+    #[must_use]
+    pub fn cutoff(&self) -> f64 {
+        self.cutoff
+    }
+
+    /// Returns the intensity of the light arriving at `point`, after attenuation.
+    #[must_use]
+    pub fn intensity_at(&self, point: Tuple) -> Color {
+        let d = (point - self.position).magnitude();
+        if d > self.cutoff {
+            Color::new(0.0, 0.0, 0.0)
+        } else {
+            self.intensity * self.attenuation.factor(d)
         }
     }
 }
@@ -0,0 +1,255 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+
+use std::fmt;
+use std::fs;
+
+#[derive(Debug)]
+pub enum PpmError {
+    Io(std::io::Error),
+    UnsupportedFormat(String),
+    Malformed {
+        line: usize,
+        expected: &'static str,
+        found: String,
+    },
+    ValueOutOfRange {
+        index: usize,
+        value: u32,
+        max_value: u32,
+    },
+    Truncated {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for PpmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PpmError::Io(e) => write!(f, "Could not read PPM file: {e}"),
+            PpmError::UnsupportedFormat(magic) => {
+                write!(f, "Unsupported image format {magic:?}, expected P3 or P6")
+            }
+            PpmError::Malformed {
+                line,
+                expected,
+                found,
+            } => write!(f, "Expected {expected} on line {line}, found {found:?}"),
+            PpmError::ValueOutOfRange {
+                index,
+                value,
+                max_value,
+            } => write!(
+                f,
+                "Color value {index} is {value}, larger than the maximum value {max_value}"
+            ),
+            PpmError::Truncated { expected, found } => write!(
+                f,
+                "Image data ended after {found} of {expected} color values"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PpmError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PpmError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for PpmError {
+    fn from(e: std::io::Error) -> Self {
+        PpmError::Io(e)
+    }
+}
+
+// Splits the textual parts of a PPM file into whitespace separated tokens, skipping
+// comments and keeping track of the line each token is on.
+struct Tokens<'a> {
+    data: &'a [u8],
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Tokens {
+            data,
+            pos: 0,
+            line: 1,
+        }
+    }
+
+    fn skip_whitespace_and_comments(&mut self) {
+        while let Some(&byte) = self.data.get(self.pos) {
+            if byte == b'#' {
+                while self.pos < self.data.len() && self.data[self.pos] != b'\n' {
+                    self.pos += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                if byte == b'\n' {
+                    self.line += 1;
+                }
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Option<&'a [u8]> {
+        self.skip_whitespace_and_comments();
+        let start = self.pos;
+        while self.pos < self.data.len()
+            && !self.data[self.pos].is_ascii_whitespace()
+            && self.data[self.pos] != b'#'
+        {
+            self.pos += 1;
+        }
+        (self.pos > start).then(|| &self.data[start..self.pos])
+    }
+
+    fn number(&mut self, expected: &'static str) -> Result<u32, PpmError> {
+        let token = self.next_token();
+        let line = self.line;
+        token
+            .and_then(|t| std::str::from_utf8(t).ok())
+            .and_then(|t| t.parse().ok())
+            .ok_or_else(|| PpmError::Malformed {
+                line,
+                expected,
+                found: token.map_or_else(
+                    || "end of file".to_string(),
+                    |t| String::from_utf8_lossy(t).into_owned(),
+                ),
+            })
+    }
+}
+
+pub struct PpmParser;
+
+impl PpmParser {
+    /// Reads a plain (P3) or raw (P6) PPM image into a canvas, scaling each channel by the
+    /// file's maximum value so that it lies between 0 and 1. Comments may appear anywhere
+    /// whitespace is allowed in the header, and in the pixel data of plain files.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if the file is not a P3 or P6 image, its header is malformed,
+    /// a color value exceeds the maximum value, or the pixel data ends early.
+    pub fn parse(data: &[u8]) -> Result<Canvas, PpmError> {
+        let mut tokens = Tokens::new(data);
+        let magic = tokens.next_token().unwrap_or_default();
+        let binary = match magic {
+            b"P3" => false,
+            b"P6" => true,
+            _ => {
+                return Err(PpmError::UnsupportedFormat(
+                    String::from_utf8_lossy(magic).into_owned(),
+                ))
+            }
+        };
+        let width = tokens.number("an image width")? as usize;
+        let height = tokens.number("an image height")? as usize;
+        let expected = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .ok_or_else(|| PpmError::Malformed {
+                line: tokens.line,
+                expected: "an image small enough to fit in memory",
+                found: format!("{width} {height}"),
+            })?;
+        let max_value = tokens.number("a maximum color value")?;
+        if !(1..=65535).contains(&max_value) {
+            return Err(PpmError::Malformed {
+                line: tokens.line,
+                expected: "a maximum color value between 1 and 65535",
+                found: max_value.to_string(),
+            });
+        }
+
+        let values = if binary {
+            // Exactly one whitespace byte separates the header from the raw pixel data.
+            let start = (tokens.pos + 1).min(data.len());
+            PpmParser::binary_values(&data[start..], expected, max_value)?
+        } else {
+            PpmParser::ascii_values(&mut tokens, expected, max_value)?
+        };
+
+        let scale = f64::from(max_value);
+        let mut canvas = Canvas::new(width, height);
+        for (i, rgb) in values.chunks_exact(3).enumerate() {
+            let color = Color::new(
+                f64::from(rgb[0]) / scale,
+                f64::from(rgb[1]) / scale,
+                f64::from(rgb[2]) / scale,
+            );
+            canvas.write_pixel(i % width, i / width, color);
+        }
+        Ok(canvas)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the file cannot be read or does not hold a valid PPM image.
+    pub fn from_file(filepath: &str) -> Result<Canvas, PpmError> {
+        PpmParser::parse(&fs::read(filepath)?)
+    }
+
+    fn ascii_values(
+        tokens: &mut Tokens,
+        expected: usize,
+        max_value: u32,
+    ) -> Result<Vec<u32>, PpmError> {
+        let mut values = Vec::with_capacity(expected.min(tokens.data.len()));
+        while values.len() < expected {
+            tokens.skip_whitespace_and_comments();
+            if tokens.pos >= tokens.data.len() {
+                return Err(PpmError::Truncated {
+                    expected,
+                    found: values.len(),
+                });
+            }
+            let value = tokens.number("a color value")?;
+            if value > max_value {
+                return Err(PpmError::ValueOutOfRange {
+                    index: values.len(),
+                    value,
+                    max_value,
+                });
+            }
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    // Values are single bytes when the maximum fits in one, and big-endian pairs otherwise.
+    fn binary_values(data: &[u8], expected: usize, max_value: u32) -> Result<Vec<u32>, PpmError> {
+        let width = if max_value < 256 { 1 } else { 2 };
+        let bytes = match expected.checked_mul(width) {
+            Some(bytes) if bytes <= data.len() => bytes,
+            _ => {
+                return Err(PpmError::Truncated {
+                    expected,
+                    found: data.len() / width,
+                })
+            }
+        };
+        let values: Vec<u32> = data[..bytes]
+            .chunks_exact(width)
+            .map(|bytes| bytes.iter().fold(0, |acc, b| (acc << 8) | u32::from(*b)))
+            .collect();
+        if let Some(index) = values.iter().position(|v| *v > max_value) {
+            return Err(PpmError::ValueOutOfRange {
+                index,
+                value: values[index],
+                max_value,
+            });
+        }
+        Ok(values)
+    }
+}
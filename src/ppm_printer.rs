@@ -1,6 +1,28 @@
 use crate::canvas::Canvas;
+use crate::tone_map::ToneMap;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, BufWriter, Write};
+
+// Plain PPM lines should not be longer than this many characters.
+const LINE_LIMIT: usize = 70;
+
+/// The two encodings of a PPM file: `Ascii` writes each channel as a decimal number (P3),
+/// while `Binary` writes it as a single byte (P6).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PpmFormat {
+    #[default]
+    Ascii,
+    Binary,
+}
+
+impl PpmFormat {
+    fn magic_number(self) -> &'static str {
+        match self {
+            PpmFormat::Ascii => "P3",
+            PpmFormat::Binary => "P6",
+        }
+    }
+}
 
 pub struct PpmPrinter;
 
@@ -10,49 +32,117 @@ impl PpmPrinter {
     /// Will return an error if the file indicated by `filepath` cannot be opened or
     /// written to.
     pub fn dump_to_file(canvas: &Canvas, filepath: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let header = PpmPrinter::header(canvas);
-        let data = PpmPrinter::pixel_data(canvas);
-        let mut file = File::create(filepath)?;
-        file.write_all((header + "\n" + data.as_str()).as_bytes())?;
+        PpmPrinter::dump_to_file_as(canvas, filepath, PpmFormat::Ascii)
+    }
+
+    /// # Errors
+    ///
+    /// Will return an error if the file indicated by `filepath` cannot be opened or
+    /// written to.
+    pub fn dump_to_file_as(
+        canvas: &Canvas,
+        filepath: &str,
+        format: PpmFormat,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut file = BufWriter::new(File::create(filepath)?);
+        PpmPrinter::write(canvas, format, &mut file)?;
+        file.flush()?;
         Ok(())
     }
-    fn header(canvas: &Canvas) -> String {
-        format!("P3\n{} {}\n255", canvas.width(), canvas.height())
+
+    /// Writes the canvas to `out` one row at a time, so the whole image is never held in
+    /// memory as text. Writers that are slow to call, such as files, should be buffered.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing to `out` fails
+    pub fn write<W: Write>(canvas: &Canvas, format: PpmFormat, out: W) -> io::Result<()> {
+        PpmPrinter::write_tone_mapped(canvas, format, &ToneMap::default(), out)
+    }
+
+    /// Writes the canvas to `out` like `write`, passing every pixel through `tone_map` before
+    /// it is quantized.
+    ///
+    /// # Errors
+    ///
+    /// Will return an error if writing to `out` fails
+    pub fn write_tone_mapped<W: Write>(
+        canvas: &Canvas,
+        format: PpmFormat,
+        tone_map: &ToneMap,
+        mut out: W,
+    ) -> io::Result<()> {
+        writeln!(out, "{}", PpmPrinter::header(canvas, format))?;
+        let mut row_data = Vec::new();
+        for row in 0..canvas.height() {
+            row_data.clear();
+            match format {
+                PpmFormat::Ascii => PpmPrinter::ascii_row(canvas, row, tone_map, &mut row_data),
+                PpmFormat::Binary => PpmPrinter::binary_row(canvas, row, tone_map, &mut row_data),
+            }
+            out.write_all(&row_data)?;
+        }
+        Ok(())
     }
 
-    // TODO: Clean this up
+    fn header(canvas: &Canvas, format: PpmFormat) -> String {
+        format!(
+            "{}\n{} {}\n255",
+            format.magic_number(),
+            canvas.width(),
+            canvas.height()
+        )
+    }
+
+    /// Returns the P3 pixel data of the whole canvas, with lines wrapped to 70 characters.
+    ///
     /// # Panics
     ///
     /// Panics if the pixel data cannot be converted into PPM format
     #[must_use]
     pub fn pixel_data(canvas: &Canvas) -> String {
-        let mut data_str = String::new();
+        let mut data = Vec::new();
         for row in 0..canvas.height() {
-            let mut color_data = Vec::with_capacity(canvas.width());
-            for col in 0..canvas.width() {
-                let pixel = canvas.pixel_at(col, row);
-                color_data.push(format!(
-                    "{} {} {}",
-                    pixel.red_to_int(),
-                    pixel.green_to_int(),
-                    pixel.blue_to_int()
-                ));
-            }
-            let mut bytes = color_data.join(" ").into_bytes();
-            let limit = 70;
-            let mut start = 0;
-            while bytes.len() - start > limit {
-                let mut i = start + limit;
-                while i > 0 && bytes[i] != b' ' {
-                    i -= 1;
+            PpmPrinter::ascii_row(canvas, row, &ToneMap::default(), &mut data);
+        }
+        String::from_utf8(data).expect("PPM pixel data is ASCII")
+    }
+
+    // Appends one row of P3 pixel data, breaking it at the last space before the line limit
+    // whenever it grows too long.
+    fn ascii_row(canvas: &Canvas, row: usize, tone_map: &ToneMap, data: &mut Vec<u8>) {
+        let mut line_start = data.len();
+        for col in 0..canvas.width() {
+            let pixel = tone_map.map_color(canvas.pixel_at(col, row));
+            for value in [
+                pixel.red_to_int(),
+                pixel.green_to_int(),
+                pixel.blue_to_int(),
+            ] {
+                let text = value.to_string();
+                if data.len() > line_start {
+                    if data.len() - line_start + 1 + text.len() > LINE_LIMIT {
+                        data.push(b'\n');
+                        line_start = data.len();
+                    } else {
+                        data.push(b' ');
+                    }
                 }
-                bytes[i] = b'\n';
-                start = i + 1;
+                data.extend_from_slice(text.as_bytes());
             }
-            data_str.push_str(std::str::from_utf8(&bytes).unwrap());
-            data_str.push('\n');
         }
-        data_str
+        data.push(b'\n');
+    }
+
+    fn binary_row(canvas: &Canvas, row: usize, tone_map: &ToneMap, data: &mut Vec<u8>) {
+        for col in 0..canvas.width() {
+            let pixel = tone_map.map_color(canvas.pixel_at(col, row));
+            data.extend_from_slice(&[
+                pixel.red_to_int(),
+                pixel.green_to_int(),
+                pixel.blue_to_int(),
+            ]);
+        }
     }
 }
 
@@ -64,7 +154,7 @@ mod test {
     #[test]
     fn test_header() {
         let c = Canvas::new(5, 3);
-        assert_eq!(PpmPrinter::header(&c), "P3\n5 3\n255");
+        assert_eq!(PpmPrinter::header(&c, PpmFormat::Ascii), "P3\n5 3\n255");
     }
 
     #[test]
@@ -93,4 +183,46 @@ mod test {
 
         assert_eq!(PpmPrinter::pixel_data(&c), expected);
     }
+
+    #[test]
+    fn wrapped_lines_stay_within_limit() {
+        let mut c = Canvas::new(23, 2);
+        for y in 0..c.height() {
+            for x in 0..c.width() {
+                let shades = [0.0, 0.04, 0.5, 1.0];
+                c.write_pixel(x, y, Color::new(shades[x % 4], 1.0, shades[(x + y) % 4]));
+            }
+        }
+        for line in PpmPrinter::pixel_data(&c).lines() {
+            assert!(line.len() <= 70);
+            assert!(!line.starts_with(' ') && !line.ends_with(' '));
+        }
+    }
+
+    #[test]
+    fn streaming_ascii_output() {
+        let mut c = Canvas::new(10, 2);
+        c.write_pixel(3, 1, Color::new(1.0, 0.8, 0.6));
+        let mut out = Vec::new();
+        PpmPrinter::write(&c, PpmFormat::Ascii, &mut out).unwrap();
+        let expected = format!(
+            "{}\n{}",
+            PpmPrinter::header(&c, PpmFormat::Ascii),
+            PpmPrinter::pixel_data(&c)
+        );
+        assert_eq!(String::from_utf8(out).unwrap(), expected);
+    }
+
+    #[test]
+    fn streaming_binary_output() {
+        let mut c = Canvas::new(2, 2);
+        c.write_pixel(0, 0, Color::new(1.5, 0.0, 0.0));
+        c.write_pixel(1, 0, Color::new(0.0, 0.5, 0.0));
+        c.write_pixel(1, 1, Color::new(-0.5, 0.0, 1.0));
+        let mut out = Vec::new();
+        PpmPrinter::write(&c, PpmFormat::Binary, &mut out).unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[255, 0, 0, 0, 128, 0, 0, 0, 0, 0, 0, 255]);
+        assert_eq!(out, expected);
+    }
 }
@@ -0,0 +1,128 @@
+use std::fmt;
+use std::io::{stdout, Stdout, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A snapshot of how far a render has got.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RenderProgress {
+    pub pixels_done: usize,
+    pub total_pixels: usize,
+    pub rows_done: usize,
+    pub total_rows: usize,
+    pub elapsed: Duration,
+}
+
+impl RenderProgress {
+    /// Returns the whole percentage of pixels rendered so far.
+    #[must_use]
+    pub fn percent_done(&self) -> usize {
+        if self.total_pixels == 0 {
+            return 100;
+        }
+        self.pixels_done * 100 / self.total_pixels
+    }
+}
+
+/// Receives progress events from `Camera::render_observed`. Events are delivered on the
+/// thread that called the render, once when it starts, after every completed row, and once
+/// when every row has been rendered.
+pub trait RenderObserver {
+    fn started(&mut self, _progress: &RenderProgress) {}
+
+    fn row_finished(&mut self, _progress: &RenderProgress) {}
+
+    fn finished(&mut self, _progress: &RenderProgress) {}
+}
+
+/// An observer that ignores every event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl RenderObserver for NoProgress {}
+
+/// Draws a text progress bar, by default to stdout.
+#[derive(Debug)]
+pub struct ProgressBar<W: Write> {
+    out: W,
+    drawn: usize,
+}
+
+impl ProgressBar<Stdout> {
+    #[must_use]
+    pub fn stdout() -> Self {
+        ProgressBar::new(stdout())
+    }
+}
+
+impl<W: Write> ProgressBar<W> {
+    #[must_use]
+    pub fn new(out: W) -> Self {
+        ProgressBar { out, drawn: 0 }
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+// A progress bar that cannot be drawn should not bring the render down with it, so write
+// errors are ignored.
+impl<W: Write> RenderObserver for ProgressBar<W> {
+    fn started(&mut self, progress: &RenderProgress) {
+        self.drawn = 0;
+        let _ = writeln!(
+            self.out,
+            "generating data for {} pixels",
+            progress.total_pixels
+        );
+    }
+
+    fn row_finished(&mut self, progress: &RenderProgress) {
+        let percent_done = progress.percent_done();
+        if percent_done / 2 > self.drawn {
+            self.drawn = percent_done / 2;
+            let bar = "=".repeat(self.drawn);
+            let _ = write!(self.out, "\r[{bar}>] {percent_done}%");
+            let _ = self.out.flush();
+        }
+    }
+
+    fn finished(&mut self, _progress: &RenderProgress) {
+        let _ = writeln!(self.out);
+    }
+}
+
+/// A flag shared between a render and whoever may want to stop it early. Clones refer to
+/// the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    #[must_use]
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+pub struct RenderCancelled;
+
+impl fmt::Display for RenderCancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Render was cancelled")
+    }
+}
+
+impl std::error::Error for RenderCancelled {}
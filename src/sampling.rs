@@ -0,0 +1,231 @@
+use crate::color::Color;
+
+/// Where the samples taken for a pixel are placed within it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SamplePattern {
+    /// Samples sit at the centres of a regular grid of cells covering the pixel.
+    #[default]
+    Grid,
+    /// Each sample is placed at a random point within its own grid cell, which trades the
+    /// regular grid's aliasing for noise while still covering the whole pixel.
+    Jittered,
+    /// Samples are placed anywhere in the pixel, independently of each other.
+    Random,
+}
+
+/// How the samples of a pixel are weighted by their distance from its centre when they
+/// are averaged into its color. Every filter covers one pixel only: samples are never
+/// shared with neighbouring pixels, so the tent and Gaussian filters are weighted averages
+/// that favour samples near the centre rather than full reconstruction filters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ReconstructionFilter {
+    /// Every sample counts equally.
+    #[default]
+    Box,
+    /// The weight falls linearly along each axis from the centre, to half of it at the
+    /// pixel's edges.
+    Tent,
+    /// The weight follows a Gaussian with a standard deviation of half a pixel, cut off at
+    /// the pixel's edges.
+    Gaussian,
+}
+
+impl ReconstructionFilter {
+    /// Returns the weight of a sample offset by `dx` and `dy` pixels from the centre.
+    #[must_use]
+    pub fn weight(self, dx: f64, dy: f64) -> f64 {
+        match self {
+            ReconstructionFilter::Box => 1.0,
+            ReconstructionFilter::Tent => (1.0 - dx.abs()).max(0.0) * (1.0 - dy.abs()).max(0.0),
+            ReconstructionFilter::Gaussian => (-2.0 * (dx * dx + dy * dy)).exp(),
+        }
+    }
+}
+
+/// Decides how many rays are cast through each pixel and how their colors are combined.
+/// The default casts a single ray through the centre of the pixel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sampler {
+    samples_per_pixel: usize,
+    pattern: SamplePattern,
+    filter: ReconstructionFilter,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Sampler::new(1, SamplePattern::Grid, ReconstructionFilter::Box)
+    }
+}
+
+impl Sampler {
+    /// Grid and jittered samples are laid out in rows of `ceil(sqrt(samples_per_pixel))`
+    /// cells, so square numbers of samples cover the pixel most evenly.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `samples_per_pixel` is zero
+    #[must_use]
+    pub fn new(
+        samples_per_pixel: usize,
+        pattern: SamplePattern,
+        filter: ReconstructionFilter,
+    ) -> Self {
+        assert!(samples_per_pixel > 0, "a pixel needs at least one sample");
+        Sampler {
+            samples_per_pixel,
+            pattern,
+            filter,
+        }
+    }
+
+    #[must_use]
+    pub fn samples_per_pixel(&self) -> usize {
+        self.samples_per_pixel
+    }
+
+    #[must_use]
+    pub fn pattern(&self) -> SamplePattern {
+        self.pattern
+    }
+
+    #[must_use]
+    pub fn filter(&self) -> ReconstructionFilter {
+        self.filter
+    }
+
+    /// Returns the positions of the samples for pixel (`x`, `y`) as offsets from its top
+    /// left corner, each between 0 and 1. Random offsets are derived from the pixel's
+    /// coordinates, so a pixel is always sampled the same way.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn offsets(&self, x: usize, y: usize) -> Vec<(f64, f64)> {
+        let n = self.samples_per_pixel;
+        let columns = (n as f64).sqrt().ceil() as usize;
+        let rows = n.div_ceil(columns);
+        let mut rng = SampleRng::for_pixel(x, y);
+        (0..n)
+            .map(|i| {
+                let (column, row) = ((i % columns) as f64, (i / columns) as f64);
+                match self.pattern {
+                    SamplePattern::Grid => {
+                        ((column + 0.5) / columns as f64, (row + 0.5) / rows as f64)
+                    }
+                    SamplePattern::Jittered => (
+                        (column + rng.next_f64()) / columns as f64,
+                        (row + rng.next_f64()) / rows as f64,
+                    ),
+                    SamplePattern::Random => (rng.next_f64(), rng.next_f64()),
+                }
+            })
+            .collect()
+    }
+
+    /// Combines the colors seen by the samples at `offsets` into a single pixel color,
+    /// weighting them with the reconstruction filter.
+    #[must_use]
+    pub fn combine(&self, offsets: &[(f64, f64)], colors: &[Color]) -> Color {
+        if let [color] = colors {
+            return *color;
+        }
+        let mut total = Color::new(0.0, 0.0, 0.0);
+        let mut total_weight = 0.0;
+        for ((dx, dy), color) in offsets.iter().zip(colors) {
+            let weight = self.filter.weight(dx - 0.5, dy - 0.5);
+            total += *color * weight;
+            total_weight += weight;
+        }
+        if total_weight > 0.0 {
+            total * (1.0 / total_weight)
+        } else {
+            total
+        }
+    }
+}
+
+/// Settings for `Camera::render_adaptive`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AdaptiveSampling {
+    /// Two colors differ when any of their channels differ by more than this.
+    pub threshold: f64,
+    /// How many times a pixel may be subdivided into quarters. Zero disables refinement.
+    pub max_depth: usize,
+}
+
+impl Default for AdaptiveSampling {
+    fn default() -> Self {
+        AdaptiveSampling {
+            threshold: 0.1,
+            max_depth: 2,
+        }
+    }
+}
+
+impl AdaptiveSampling {
+    #[must_use]
+    pub fn new(threshold: f64, max_depth: usize) -> Self {
+        AdaptiveSampling {
+            threshold,
+            max_depth,
+        }
+    }
+
+    #[must_use]
+    pub fn differs(&self, a: Color, b: Color) -> bool {
+        (a.red - b.red).abs() > self.threshold
+            || (a.green - b.green).abs() > self.threshold
+            || (a.blue - b.blue).abs() > self.threshold
+    }
+}
+
+/// Counts of the work done by an adaptive render.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveStats {
+    pub pixels: usize,
+    /// Pixels that differed from a neighbour and were sampled again.
+    pub refined_pixels: usize,
+    /// Rays cast from the camera, including the first sample of every pixel.
+    pub samples: usize,
+}
+
+impl AdaptiveStats {
+    /// The average number of rays cast from the camera through each pixel.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn samples_per_pixel(&self) -> f64 {
+        if self.pixels == 0 {
+            0.0
+        } else {
+            self.samples as f64 / self.pixels as f64
+        }
+    }
+}
+
+// A small SplitMix64 generator. Seeding it from the pixel coordinates, or the point being
+// shaded, keeps renders reproducible and independent of which thread draws each row.
+pub(crate) struct SampleRng(u64);
+
+impl SampleRng {
+    pub(crate) fn new(seed: u64) -> Self {
+        SampleRng(seed ^ 0x5851_f42d_4c95_7f2d)
+    }
+
+    fn for_pixel(x: usize, y: usize) -> Self {
+        SampleRng::new((x as u64) << 32 ^ (y as u64))
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uses the top 53 bits, giving a value in [0, 1).
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -1,42 +1,113 @@
+use crate::bounds::BoundingBox;
+use crate::bvh::Bvh;
 use crate::color::Color;
 use crate::intersection::Intersection;
 use crate::material::Material;
 use crate::pattern::Pattern;
 use crate::ray::Ray;
-use crate::transform::Transform;
+use crate::transform::{DegenerateTransform, Transform};
 use crate::tuple::Tuple;
-use crate::EPSILON;
+use crate::{float_near_equal, EPSILON};
 
 #[allow(clippy::module_name_repetitions)]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ShapeType {
     Sphere,
     Plane,
+    Cube,
+    Cylinder {
+        minimum: f64,
+        maximum: f64,
+        closed: bool,
+    },
+    Cone {
+        minimum: f64,
+        maximum: f64,
+        closed: bool,
+    },
+    Triangle {
+        p1: Tuple,
+        p2: Tuple,
+        p3: Tuple,
+        e1: Tuple,
+        e2: Tuple,
+        normal: Tuple,
+    },
+    SmoothTriangle {
+        p1: Tuple,
+        p2: Tuple,
+        p3: Tuple,
+        n1: Tuple,
+        n2: Tuple,
+        n3: Tuple,
+        e1: Tuple,
+        e2: Tuple,
+    },
+    Group {
+        children: Vec<Shape>,
+        bvh: Option<Bvh>,
+    },
+    Csg {
+        operation: CsgOperation,
+        children: Box<[Shape; 2]>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsgOperation {
+    Union,
+    Intersection,
+    Difference,
+}
+
+impl CsgOperation {
+    /// Decides whether a hit on one side of a CSG shape lies on the combined surface, given
+    /// whether it struck the left child and whether the ray is currently inside each child.
+    #[must_use]
+    pub fn allows(self, left_hit: bool, in_left: bool, in_right: bool) -> bool {
+        match self {
+            CsgOperation::Union => (left_hit && !in_right) || (!left_hit && !in_left),
+            CsgOperation::Intersection => (left_hit && in_right) || (!left_hit && in_left),
+            CsgOperation::Difference => (left_hit && !in_right) || (!left_hit && in_left),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Shape {
     material: Material,
     transform: Transform,
+    // The inverse and inverse transpose of `transform`, cached whenever it changes. `None`
+    // if the transform cannot be inverted.
+    inverse: Option<(Transform, Transform)>,
     id: Option<usize>,
     kind: ShapeType,
     casts_shadow: bool,
+    // The inverse and inverse transpose transforms of every group containing this shape,
+    // nearest first, or `None` if any of them cannot be inverted.
+    parents: Option<Vec<(Transform, Transform)>>,
 }
 
 // TODO: make materials and transforms copies instead of references
 
 impl Shape {
-    #[must_use]
-    pub fn sphere() -> Shape {
+    fn with_kind(kind: ShapeType) -> Shape {
         Shape {
             material: Material::new(),
             transform: Transform::new(),
+            inverse: Some((Transform::new(), Transform::new())),
             id: None,
-            kind: ShapeType::Sphere,
+            kind,
             casts_shadow: true,
+            parents: Some(vec![]),
         }
     }
 
+    #[must_use]
+    pub fn sphere() -> Shape {
+        Shape::with_kind(ShapeType::Sphere)
+    }
+
     #[must_use]
     pub fn glass_sphere() -> Self {
         let mut s = Shape::sphere();
@@ -47,17 +118,288 @@ impl Shape {
 
     #[must_use]
     pub fn plane() -> Shape {
-        Shape {
-            material: Material::new(),
-            transform: Transform::new(),
-            id: None,
-            kind: ShapeType::Plane,
-            casts_shadow: true,
+        Shape::with_kind(ShapeType::Plane)
+    }
+
+    #[must_use]
+    pub fn cube() -> Shape {
+        Shape::with_kind(ShapeType::Cube)
+    }
+
+    #[must_use]
+    pub fn cylinder() -> Shape {
+        Shape::truncated_cylinder(f64::NEG_INFINITY, f64::INFINITY, false)
+    }
+
+    /// Creates a unit-radius cylinder around the y axis spanning `minimum` to `maximum`
+    /// (exclusive). When `closed` is set the ends are capped.
+    #[must_use]
+    pub fn truncated_cylinder(minimum: f64, maximum: f64, closed: bool) -> Shape {
+        Shape::with_kind(ShapeType::Cylinder {
+            minimum,
+            maximum,
+            closed,
+        })
+    }
+
+    #[must_use]
+    pub fn cone() -> Shape {
+        Shape::truncated_cone(f64::NEG_INFINITY, f64::INFINITY, false)
+    }
+
+    /// Creates a double-napped cone around the y axis, with its apex at the origin, spanning
+    /// `minimum` to `maximum` (exclusive). When `closed` is set the ends are capped.
+    #[must_use]
+    pub fn truncated_cone(minimum: f64, maximum: f64, closed: bool) -> Shape {
+        Shape::with_kind(ShapeType::Cone {
+            minimum,
+            maximum,
+            closed,
+        })
+    }
+
+    #[must_use]
+    pub fn triangle(p1: Tuple, p2: Tuple, p3: Tuple) -> Shape {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        Shape::with_kind(ShapeType::Triangle {
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross_product(&e1).normalize(),
+        })
+    }
+
+    /// Creates a triangle whose normal is interpolated across its face from the normals
+    /// given at each vertex.
+    #[must_use]
+    pub fn smooth_triangle(
+        p1: Tuple,
+        p2: Tuple,
+        p3: Tuple,
+        n1: Tuple,
+        n2: Tuple,
+        n3: Tuple,
+    ) -> Shape {
+        Shape::with_kind(ShapeType::SmoothTriangle {
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+        })
+    }
+
+    /// Creates an empty group. Children added to a group are positioned relative to it, so
+    /// transforming the group transforms everything inside it.
+    #[must_use]
+    pub fn group() -> Shape {
+        Shape::with_kind(ShapeType::Group {
+            children: vec![],
+            bvh: None,
+        })
+    }
+
+    /// Creates a shape from the union, intersection or difference of `left` and `right`.
+    #[must_use]
+    pub fn csg(operation: CsgOperation, left: Shape, right: Shape) -> Shape {
+        let mut s = Shape::with_kind(ShapeType::Csg {
+            operation,
+            children: Box::new([left, right]),
+        });
+        s.set_parents(Some(vec![]));
+        s
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if `self` is not a group
+    pub fn add_child(&mut self, mut child: Shape) {
+        child.set_parents(self.parents_of_children());
+        match self.kind {
+            ShapeType::Group {
+                ref mut children,
+                ref mut bvh,
+            } => {
+                children.push(child);
+                *bvh = None;
+            }
+            _ => panic!("only groups can have children"),
+        }
+    }
+
+    #[must_use]
+    pub fn children(&self) -> &[Shape] {
+        match self.kind {
+            ShapeType::Group { ref children, .. } => children,
+            ShapeType::Csg { ref children, .. } => &children[..],
+            _ => &[],
+        }
+    }
+
+    fn children_mut(&mut self) -> &mut [Shape] {
+        match self.kind {
+            ShapeType::Group {
+                ref mut children, ..
+            } => children,
+            ShapeType::Csg {
+                ref mut children, ..
+            } => &mut children[..],
+            _ => &mut [],
         }
     }
 
+    #[must_use]
+    pub fn id(&self) -> Option<usize> {
+        self.id
+    }
+
+    // Records the transforms of the groups above this shape and passes the chain, extended
+    // by this shape's own transform, down to any children.
+    fn set_parents(&mut self, parents: Option<Vec<(Transform, Transform)>>) {
+        self.parents = parents;
+        let child_parents = self.parents_of_children();
+        for child in self.children_mut() {
+            child.set_parents(child_parents.clone());
+        }
+    }
+
+    fn parents_of_children(&self) -> Option<Vec<(Transform, Transform)>> {
+        let mut parents = vec![self.inverse?];
+        parents.extend(self.parents.as_ref()?.iter().copied());
+        Some(parents)
+    }
+
+    fn transform_changed(&mut self) {
+        self.inverse = self
+            .transform
+            .try_inverse()
+            .ok()
+            .map(|inverse| (inverse, inverse.transpose()));
+        if !self.children().is_empty() {
+            self.set_parents(self.parents.clone());
+        }
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &ShapeType {
+        &self.kind
+    }
+
+    /// Sets the id of this shape. The children of a group are numbered depth first from
+    /// `id + 1`.
     pub fn set_id(&mut self, id: usize) {
-        self.id = Some(id);
+        self.assign_ids(id);
+    }
+
+    // Numbers this shape and its descendants depth first, returning the next unused id.
+    pub(crate) fn assign_ids(&mut self, first: usize) -> usize {
+        self.id = Some(first);
+        let mut next = first + 1;
+        for child in self.children_mut() {
+            next = child.assign_ids(next);
+        }
+        next
+    }
+
+    // Checks that the transforms of this shape, its descendants and their patterns can all be
+    // inverted. Ids must already have been assigned.
+    pub(crate) fn check_transforms(&self) -> Result<(), DegenerateTransform> {
+        let id = self.id.unwrap_or_default();
+        if self.inverse.is_none() {
+            return Err(DegenerateTransform::Object(id));
+        }
+        if let Some(pattern) = &self.material.pattern {
+            if !pattern.transform().is_invertible() {
+                return Err(DegenerateTransform::Pattern(id));
+            }
+        }
+        self.children().iter().try_for_each(Shape::check_transforms)
+    }
+
+    /// Builds bounding volume hierarchies over the children of this group and any groups
+    /// nested inside it. `World::add_object` does this automatically; groups that change
+    /// afterwards fall back to testing every child until this is called again.
+    pub fn build_bvh(&mut self) {
+        for child in self.children_mut() {
+            child.build_bvh();
+        }
+        if let ShapeType::Group {
+            ref children,
+            ref mut bvh,
+        } = self.kind
+        {
+            let bounds: Vec<BoundingBox> =
+                children.iter().map(Shape::parent_space_bounds).collect();
+            *bvh = Some(Bvh::build(&bounds));
+        }
+    }
+
+    /// Returns the box enclosing this shape in its own object space.
+    #[must_use]
+    pub fn bounds(&self) -> BoundingBox {
+        let unit = |min: f64, max: f64| {
+            BoundingBox::new(Tuple::point(min, min, min), Tuple::point(max, max, max))
+        };
+        match self.kind {
+            ShapeType::Sphere | ShapeType::Cube => unit(-1.0, 1.0),
+            ShapeType::Plane => BoundingBox::new(
+                Tuple::point(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+                Tuple::point(f64::INFINITY, 0.0, f64::INFINITY),
+            ),
+            ShapeType::Cylinder {
+                minimum, maximum, ..
+            } => BoundingBox::new(
+                Tuple::point(-1.0, minimum, -1.0),
+                Tuple::point(1.0, maximum, 1.0),
+            ),
+            ShapeType::Cone {
+                minimum, maximum, ..
+            } => {
+                let radius = minimum.abs().max(maximum.abs());
+                BoundingBox::new(
+                    Tuple::point(-radius, minimum, -radius),
+                    Tuple::point(radius, maximum, radius),
+                )
+            }
+            ShapeType::Triangle { p1, p2, p3, .. }
+            | ShapeType::SmoothTriangle { p1, p2, p3, .. } => {
+                let mut b = BoundingBox::empty();
+                b.add_point(p1);
+                b.add_point(p2);
+                b.add_point(p3);
+                b
+            }
+            ShapeType::Group { .. } | ShapeType::Csg { .. } => {
+                let mut b = BoundingBox::empty();
+                for child in self.children() {
+                    b.merge(&child.parent_space_bounds());
+                }
+                b
+            }
+        }
+    }
+
+    /// Returns the box enclosing this shape in the space of whatever contains it, taking
+    /// its transform into account.
+    #[must_use]
+    pub fn parent_space_bounds(&self) -> BoundingBox {
+        self.bounds().transform(&self.transform)
+    }
+
+    /// Finds the shape with the given id in this shape's subtree.
+    #[must_use]
+    pub fn find(&self, id: usize) -> Option<&Shape> {
+        if self.id == Some(id) {
+            Some(self)
+        } else {
+            find_shape(self.children(), id)
+        }
     }
 
     pub fn set_casts_shadow(&mut self, new_value: bool) {
@@ -112,62 +454,226 @@ impl Shape {
     }
 
     pub fn scale(&mut self, x: f64, y: f64, z: f64) {
-        let new_transform = self.transform.clone().scale(x, y, z);
+        let new_transform = self.transform.scale(x, y, z);
         self.transform = new_transform;
+        self.transform_changed();
     }
 
     pub fn translate(&mut self, x: f64, y: f64, z: f64) {
-        self.transform = self.transform.clone().translate(x, y, z);
+        self.transform = self.transform.translate(x, y, z);
+        self.transform_changed();
     }
 
     pub fn rotate_x(&mut self, r: f64) {
-        self.transform = self.transform.clone().rotate_x(r);
+        self.transform = self.transform.rotate_x(r);
+        self.transform_changed();
     }
 
     pub fn rotate_z(&mut self, r: f64) {
-        self.transform = self.transform.clone().rotate_z(r);
+        self.transform = self.transform.rotate_z(r);
+        self.transform_changed();
     }
 
     pub fn rotate_y(&mut self, r: f64) {
-        self.transform = self.transform.clone().rotate_y(r);
+        self.transform = self.transform.rotate_y(r);
+        self.transform_changed();
     }
 
     pub fn shear(&mut self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) {
-        self.transform = self.transform.clone().shear(xy, xz, yx, yz, zx, zy);
+        self.transform = self.transform.shear(xy, xz, yx, yz, zx, zy);
+        self.transform_changed();
     }
 
+    /// Computes the world space normal at `point`. Smooth triangles need to know where they
+    /// were hit to interpolate their normal, so prefer `normal_at_hit` when an
+    /// `Intersection` is available; here they report the normal of their first vertex.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the transform of this shape or of a group containing it cannot be
+    /// inverted
     #[must_use]
     pub fn normal_at(&self, point: Tuple) -> Tuple {
-        match self.kind {
-            ShapeType::Sphere => {
-                let mut t = self.transform.clone().inverse();
-                let object_point = t.transform(&point);
-                let object_normal = object_point - Tuple::point(0.0, 0.0, 0.0);
-                t = t.transpose();
-                let mut world_normal = t.transform(&object_normal);
-                world_normal.w = 0.0;
-                world_normal.normalize()
-            }
+        self.normal_at_hit(point, &Intersection::new(0.0, self.id.unwrap_or(0)))
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if called on a group or CSG shape, which have no surface of their own, or
+    /// if the transform of this shape or of a group containing it cannot be inverted
+    #[must_use]
+    pub fn normal_at_hit(&self, point: Tuple, hit: &Intersection) -> Tuple {
+        let object_point = self.world_to_object(point);
+        let object_normal = match self.kind {
+            ShapeType::Sphere => object_point - Tuple::point(0.0, 0.0, 0.0),
             ShapeType::Plane => Tuple::vector(0.0, 1.0, 0.0),
+            ShapeType::Cube => cube_normal(object_point),
+            ShapeType::Cylinder {
+                minimum, maximum, ..
+            } => cylinder_normal(object_point, minimum, maximum),
+            ShapeType::Cone {
+                minimum, maximum, ..
+            } => cone_normal(object_point, minimum, maximum),
+            ShapeType::Triangle { normal, .. } => normal,
+            ShapeType::SmoothTriangle { n1, n2, n3, .. } => {
+                n2 * hit.u + n3 * hit.v + n1 * (1.0 - hit.u - hit.v)
+            }
+            ShapeType::Group { .. } | ShapeType::Csg { .. } => {
+                panic!("groups and CSG shapes do not have normals")
+            }
+        };
+        self.normal_to_world(object_normal)
+    }
+
+    /// Converts a point in world space to this shape's object space, applying the inverse
+    /// transform of each enclosing group from the outermost inwards.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the transform of this shape or of a group containing it cannot be
+    /// inverted
+    #[must_use]
+    pub fn world_to_object(&self, point: Tuple) -> Tuple {
+        let (own, parents) = self.inverses();
+        let mut point = point;
+        for (inverse, _) in parents.iter().rev() {
+            point = inverse.transform(&point);
+        }
+        own.0.transform(&point)
+    }
+
+    /// Converts a normal in this shape's object space to world space, applying the inverse
+    /// transpose of this shape's transform and then of each enclosing group.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the transform of this shape or of a group containing it cannot be
+    /// inverted
+    #[must_use]
+    pub fn normal_to_world(&self, normal: Tuple) -> Tuple {
+        let (own, parents) = self.inverses();
+        let mut normal = normal;
+        for (_, t) in std::iter::once(own).chain(parents) {
+            normal = t.transform(&normal);
+            normal.w = 0.0;
+            normal = normal.normalize();
+        }
+        normal
+    }
+
+    // The cached inverses of this shape and the groups containing it, nearest first.
+    fn inverses(&self) -> (&(Transform, Transform), &[(Transform, Transform)]) {
+        match (&self.inverse, &self.parents) {
+            (Some(own), Some(parents)) => (own, parents),
+            _ => panic!("the transform of this shape or a group containing it cannot be inverted"),
         }
     }
 
+    /// Returns `None` if the shape has not been given an id or its transform cannot be
+    /// inverted.
     #[must_use]
     pub fn intersect(&self, ray: &Ray) -> Option<Vec<Intersection>> {
-        if let Some(id) = self.id {
+        if let (Some(id), Some((inverse, _))) = (self.id, &self.inverse) {
             let hits = match self.kind {
-                ShapeType::Sphere => sphere_intersect(&self.transform, ray, id),
-                ShapeType::Plane => plane_intersect(&self.transform, ray, id),
+                ShapeType::Sphere => sphere_intersect(inverse, ray, id),
+                ShapeType::Plane => plane_intersect(inverse, ray, id),
+                ShapeType::Cube => cube_intersect(inverse, ray, id),
+                ShapeType::Cylinder {
+                    minimum,
+                    maximum,
+                    closed,
+                } => cylinder_intersect(inverse, ray, minimum, maximum, closed, id),
+                ShapeType::Cone {
+                    minimum,
+                    maximum,
+                    closed,
+                } => cone_intersect(inverse, ray, minimum, maximum, closed, id),
+                ShapeType::Triangle { p1, e1, e2, .. }
+                | ShapeType::SmoothTriangle { p1, e1, e2, .. } => {
+                    triangle_intersect(inverse, ray, p1, e1, e2, id)
+                }
+                ShapeType::Group {
+                    ref children,
+                    ref bvh,
+                } => {
+                    let local_ray = inverse.transform_ray(ray);
+                    let candidates = match bvh {
+                        Some(bvh) => bvh.candidates(&local_ray),
+                        None => (0..children.len()).collect(),
+                    };
+                    candidates
+                        .into_iter()
+                        .filter_map(|idx| children[idx].intersect(&local_ray))
+                        .flatten()
+                        .collect()
+                }
+                ShapeType::Csg { .. } => {
+                    let local_ray = inverse.transform_ray(ray);
+                    let xs: Vec<Intersection> = self
+                        .children()
+                        .iter()
+                        .filter_map(|child| child.intersect(&local_ray))
+                        .flatten()
+                        .collect();
+                    self.filter_intersections(&xs)
+                }
             };
             Some(hits)
         } else {
             None
         }
     }
+
+    /// Keeps only the intersections that lie on the surface of this CSG shape. Shapes that
+    /// are not CSG shapes return `xs` unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if any intersection has a `t` of `NaN`
+    #[must_use]
+    pub fn filter_intersections(&self, xs: &[Intersection]) -> Vec<Intersection> {
+        let ShapeType::Csg {
+            operation,
+            ref children,
+        } = self.kind
+        else {
+            return xs.to_vec();
+        };
+        let mut sorted = xs.to_vec();
+        sorted.sort_unstable_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+
+        let mut in_left = false;
+        let mut in_right = false;
+        let mut result = vec![];
+        for i in sorted {
+            let left_hit = children[0].find(i.object_id).is_some();
+            if operation.allows(left_hit, in_left, in_right) {
+                result.push(i);
+            }
+            if left_hit {
+                in_left = !in_left;
+            } else {
+                in_right = !in_right;
+            }
+        }
+        result
+    }
 }
 
-fn sphere_intersect(transform: &Transform, ray: &Ray, object_id: usize) -> Vec<Intersection> {
-    let transformed_ray = transform.clone().inverse().transform_ray(ray);
+/// Finds the shape with the given id among `shapes` or their descendants. Ids are handed
+/// out depth first, so `shapes` is ordered by id and each subtree covers a contiguous range.
+#[must_use]
+pub fn find_shape(shapes: &[Shape], id: usize) -> Option<&Shape> {
+    let idx = shapes.partition_point(|s| s.id.is_some_and(|first| first <= id));
+    if idx == 0 {
+        None
+    } else {
+        shapes[idx - 1].find(id)
+    }
+}
+
+fn sphere_intersect(inverse: &Transform, ray: &Ray, object_id: usize) -> Vec<Intersection> {
+    let transformed_ray = inverse.transform_ray(ray);
     let sphere_to_ray = transformed_ray.origin - Tuple::point(0.0, 0.0, 0.0);
 
     let a = transformed_ray
@@ -190,8 +696,8 @@ fn sphere_intersect(transform: &Transform, ray: &Ray, object_id: usize) -> Vec<I
     }
 }
 
-fn plane_intersect(transform: &Transform, ray: &Ray, object_id: usize) -> Vec<Intersection> {
-    let ray = transform.clone().inverse().transform_ray(ray);
+fn plane_intersect(inverse: &Transform, ray: &Ray, object_id: usize) -> Vec<Intersection> {
+    let ray = inverse.transform_ray(ray);
     if ray.direction.y.abs() < EPSILON {
         return vec![];
     }
@@ -199,3 +705,233 @@ fn plane_intersect(transform: &Transform, ray: &Ray, object_id: usize) -> Vec<In
     let t = -ray.origin.y / ray.direction.y;
     vec![Intersection::new(t, object_id)]
 }
+
+fn cube_intersect(inverse: &Transform, ray: &Ray, object_id: usize) -> Vec<Intersection> {
+    let ray = inverse.transform_ray(ray);
+    let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x);
+    let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y);
+    let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z);
+
+    let tmin = xtmin.max(ytmin).max(ztmin);
+    let tmax = xtmax.min(ytmax).min(ztmax);
+    if tmin > tmax {
+        return vec![];
+    }
+    vec![
+        Intersection::new(tmin, object_id),
+        Intersection::new(tmax, object_id),
+    ]
+}
+
+// Returns the distances at which the ray crosses the two faces of the unit cube
+// perpendicular to a single axis, ordered nearest first.
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (
+            tmin_numerator * f64::INFINITY,
+            tmax_numerator * f64::INFINITY,
+        )
+    };
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+fn cube_normal(point: Tuple) -> Tuple {
+    let maxc = point.x.abs().max(point.y.abs()).max(point.z.abs());
+    if float_near_equal(maxc, point.x.abs()) {
+        Tuple::vector(point.x, 0.0, 0.0)
+    } else if float_near_equal(maxc, point.y.abs()) {
+        Tuple::vector(0.0, point.y, 0.0)
+    } else {
+        Tuple::vector(0.0, 0.0, point.z)
+    }
+}
+
+fn cylinder_intersect(
+    inverse: &Transform,
+    ray: &Ray,
+    minimum: f64,
+    maximum: f64,
+    closed: bool,
+    object_id: usize,
+) -> Vec<Intersection> {
+    let ray = inverse.transform_ray(ray);
+    let mut xs = vec![];
+
+    let a = ray.direction.x * ray.direction.x + ray.direction.z * ray.direction.z;
+    // A ray parallel to the y axis can only hit the caps.
+    if a.abs() >= EPSILON {
+        let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+        let c = ray.origin.x * ray.origin.x + ray.origin.z * ray.origin.z - 1.0;
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return xs;
+        }
+        let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+        let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+        if t0 > t1 {
+            std::mem::swap(&mut t0, &mut t1);
+        }
+        for t in [t0, t1] {
+            let y = ray.origin.y + t * ray.direction.y;
+            if minimum < y && y < maximum {
+                xs.push(Intersection::new(t, object_id));
+            }
+        }
+    }
+
+    if closed {
+        caps_intersect(&ray, minimum, maximum, 1.0, 1.0, object_id, &mut xs);
+    }
+    xs
+}
+
+fn cone_intersect(
+    inverse: &Transform,
+    ray: &Ray,
+    minimum: f64,
+    maximum: f64,
+    closed: bool,
+    object_id: usize,
+) -> Vec<Intersection> {
+    let ray = inverse.transform_ray(ray);
+    let mut xs = vec![];
+
+    let a = ray.direction.x * ray.direction.x - ray.direction.y * ray.direction.y
+        + ray.direction.z * ray.direction.z;
+    let b = 2.0 * ray.origin.x * ray.direction.x - 2.0 * ray.origin.y * ray.direction.y
+        + 2.0 * ray.origin.z * ray.direction.z;
+    let c = ray.origin.x * ray.origin.x - ray.origin.y * ray.origin.y + ray.origin.z * ray.origin.z;
+
+    let mut ts = vec![];
+    if a.abs() < EPSILON {
+        // The ray is parallel to one of the cone's halves, so it can cross the other half
+        // at most once. If b is also zero the ray misses the walls entirely.
+        if b.abs() >= EPSILON {
+            ts.push(-c / (2.0 * b));
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return xs;
+        }
+        let t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+        let t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+        ts.push(t0.min(t1));
+        ts.push(t0.max(t1));
+    }
+    for t in ts {
+        let y = ray.origin.y + t * ray.direction.y;
+        if minimum < y && y < maximum {
+            xs.push(Intersection::new(t, object_id));
+        }
+    }
+
+    if closed {
+        caps_intersect(
+            &ray,
+            minimum,
+            maximum,
+            minimum.abs(),
+            maximum.abs(),
+            object_id,
+            &mut xs,
+        );
+    }
+    xs
+}
+
+// Moller-Trumbore intersection. The barycentric coordinates of the hit are kept on the
+// intersection so smooth triangles can interpolate their vertex normals.
+fn triangle_intersect(
+    inverse: &Transform,
+    ray: &Ray,
+    p1: Tuple,
+    e1: Tuple,
+    e2: Tuple,
+    object_id: usize,
+) -> Vec<Intersection> {
+    let ray = inverse.transform_ray(ray);
+    let dir_cross_e2 = ray.direction.cross_product(&e2);
+    let det = e1.dot_product(&dir_cross_e2);
+    if det.abs() < EPSILON {
+        return vec![];
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * p1_to_origin.dot_product(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return vec![];
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross_product(&e1);
+    let v = f * ray.direction.dot_product(&origin_cross_e1);
+    if v < 0.0 || (u + v) > 1.0 {
+        return vec![];
+    }
+
+    let t = f * e2.dot_product(&origin_cross_e1);
+    vec![Intersection::with_uv(t, object_id, u, v)]
+}
+
+// Intersects the ray (already in object space) with the discs closing the ends of a
+// cylinder or cone, where each disc's radius is given for the respective end.
+fn caps_intersect(
+    ray: &Ray,
+    minimum: f64,
+    maximum: f64,
+    minimum_radius: f64,
+    maximum_radius: f64,
+    object_id: usize,
+    xs: &mut Vec<Intersection>,
+) {
+    if ray.direction.y.abs() < EPSILON {
+        return;
+    }
+    for (y, radius) in [(minimum, minimum_radius), (maximum, maximum_radius)] {
+        let t = (y - ray.origin.y) / ray.direction.y;
+        if check_cap(ray, t, radius) {
+            xs.push(Intersection::new(t, object_id));
+        }
+    }
+}
+
+fn check_cap(ray: &Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    (x * x + z * z) <= radius * radius
+}
+
+fn cylinder_normal(point: Tuple, minimum: f64, maximum: f64) -> Tuple {
+    let dist = point.x * point.x + point.z * point.z;
+    if dist < 1.0 && point.y >= maximum - EPSILON {
+        Tuple::vector(0.0, 1.0, 0.0)
+    } else if dist < 1.0 && point.y <= minimum + EPSILON {
+        Tuple::vector(0.0, -1.0, 0.0)
+    } else {
+        Tuple::vector(point.x, 0.0, point.z)
+    }
+}
+
+fn cone_normal(point: Tuple, minimum: f64, maximum: f64) -> Tuple {
+    let dist = point.x * point.x + point.z * point.z;
+    if dist < maximum * maximum && point.y >= maximum - EPSILON {
+        Tuple::vector(0.0, 1.0, 0.0)
+    } else if dist < minimum * minimum && point.y <= minimum + EPSILON {
+        Tuple::vector(0.0, -1.0, 0.0)
+    } else {
+        let mut y = dist.sqrt();
+        if point.y > 0.0 {
+            y = -y;
+        }
+        Tuple::vector(point.x, y, point.z)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::color::Color;
+use crate::tuple::Tuple;
+use crate::EPSILON;
+
+/// A light that shines from a point in a cone around `direction`. Points within
+/// `inner_angle` of the direction receive the full intensity, which falls off smoothly to
+/// nothing at `outer_angle`. Both angles are measured from the cone's axis, in radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpotLight {
+    pub position: Tuple,
+    pub direction: Tuple,
+    pub inner_angle: f64,
+    pub outer_angle: f64,
+    pub intensity: Color,
+    pub id: usize,
+}
+
+impl SpotLight {
+    /// # Panics
+    ///
+    /// Will panic if `direction` has no length or `inner_angle` is larger than
+    /// `outer_angle`
+    #[must_use]
+    pub fn new(
+        position: Tuple,
+        direction: Tuple,
+        inner_angle: f64,
+        outer_angle: f64,
+        intensity: Color,
+    ) -> Self {
+        assert!(
+            direction.magnitude() > 0.0,
+            "a spot light needs a direction to point in"
+        );
+        assert!(
+            inner_angle <= outer_angle,
+            "a spot light's inner angle cannot be larger than its outer angle"
+        );
+        SpotLight {
+            position,
+            direction: direction.normalize(),
+            inner_angle,
+            outer_angle,
+            intensity,
+            id: 0,
+        }
+    }
+
+    /// Returns how much of the light reaches `point` because of the shape of the cone,
+    /// from 0 outside it to 1 inside the inner angle. A point at the light itself lies at
+    /// the tip of the cone and receives all of it.
+    #[must_use]
+    pub fn falloff(&self, point: Tuple) -> f64 {
+        let v = point - self.position;
+        let distance = v.magnitude();
+        if distance < EPSILON {
+            return 1.0;
+        }
+        let cos_angle = v.dot_product(&self.direction) / distance;
+        let (cos_outer, cos_inner) = (self.outer_angle.cos(), self.inner_angle.cos());
+        if cos_angle >= cos_inner {
+            1.0
+        } else if cos_angle <= cos_outer {
+            0.0
+        } else {
+            let t = (cos_angle - cos_outer) / (cos_inner - cos_outer);
+            t * t * (3.0 - 2.0 * t)
+        }
+    }
+}
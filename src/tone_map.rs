@@ -0,0 +1,112 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+
+/// The curve used to bring unbounded scene colors into the range 0 to 1.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ToneMapOperator {
+    /// Cuts every channel off at 1, which is how colors have always been written.
+    #[default]
+    Clamp,
+    /// Maps each channel `c` to `c / (1 + c)`, compressing highlights without ever
+    /// reaching white.
+    Reinhard,
+    /// Krzysztof Narkowicz's fit of the ACES filmic curve, which adds some contrast to the
+    /// midtones and rolls highlights off to white.
+    Aces,
+}
+
+impl ToneMapOperator {
+    fn map(self, value: f64) -> f64 {
+        let value = value.max(0.0);
+        match self {
+            ToneMapOperator::Clamp => value.min(1.0),
+            ToneMapOperator::Reinhard => value / (1.0 + value),
+            ToneMapOperator::Aces => {
+                let mapped =
+                    (value * (2.51 * value + 0.03)) / (value * (2.43 * value + 0.59) + 0.14);
+                mapped.clamp(0.0, 1.0)
+            }
+        }
+    }
+}
+
+/// How tone mapped values are encoded before being quantized by an image writer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransferFunction {
+    /// Values are written as they are.
+    #[default]
+    Linear,
+    /// Values are gamma encoded with the sRGB transfer function, which most image viewers
+    /// expect of PPM and PNG files.
+    Srgb,
+}
+
+impl TransferFunction {
+    fn encode(self, value: f64) -> f64 {
+        match self {
+            TransferFunction::Linear => value,
+            TransferFunction::Srgb => srgb_encode(value),
+        }
+    }
+}
+
+/// A post-processing stage that turns the linear colors of a rendered canvas into display
+/// colors: the exposure is applied first, then the tone mapping operator, then the transfer
+/// function. The default leaves colors between 0 and 1 unchanged and clamps the rest.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ToneMap {
+    /// Exposure adjustment in stops, so each step of 1 doubles the brightness.
+    pub exposure: f64,
+    pub operator: ToneMapOperator,
+    pub transfer: TransferFunction,
+}
+
+impl ToneMap {
+    #[must_use]
+    pub fn new(exposure: f64, operator: ToneMapOperator, transfer: TransferFunction) -> Self {
+        ToneMap {
+            exposure,
+            operator,
+            transfer,
+        }
+    }
+
+    #[must_use]
+    pub fn map_color(&self, color: Color) -> Color {
+        let scale = self.exposure.exp2();
+        let map = |value: f64| self.transfer.encode(self.operator.map(value * scale));
+        Color::new(map(color.red), map(color.green), map(color.blue))
+    }
+
+    /// Returns a copy of the canvas with every pixel tone mapped.
+    #[must_use]
+    pub fn apply(&self, canvas: &Canvas) -> Canvas {
+        let mut mapped = Canvas::new(canvas.width(), canvas.height());
+        for y in 0..canvas.height() {
+            for x in 0..canvas.width() {
+                mapped.write_pixel(x, y, self.map_color(canvas.pixel_at(x, y)));
+            }
+        }
+        mapped
+    }
+}
+
+/// Encodes a linear value between 0 and 1 with the sRGB transfer function.
+#[must_use]
+pub fn srgb_encode(linear: f64) -> f64 {
+    if linear <= 0.003_130_8 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Decodes an sRGB encoded value between 0 and 1 back into a linear one.
+#[must_use]
+pub fn srgb_decode(encoded: f64) -> f64 {
+    if encoded <= 0.040_45 {
+        encoded / 12.92
+    } else {
+        ((encoded + 0.055) / 1.055).powf(2.4)
+    }
+}
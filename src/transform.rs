@@ -1,10 +1,37 @@
-use crate::matrix::Matrix;
+use crate::matrix::{Matrix4, SingularMatrix};
 use crate::ray::Ray;
 use crate::tuple::Tuple;
 
-#[derive(Clone, Debug, PartialEq)]
+use std::fmt;
+
+/// Identifies what has a transform that cannot be inverted, such as a scale of zero along
+/// one axis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DegenerateTransform {
+    Object(usize),
+    Pattern(usize),
+    Camera,
+}
+
+impl fmt::Display for DegenerateTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DegenerateTransform::Object(id) => {
+                write!(f, "Object {id} has a degenerate transform")
+            }
+            DegenerateTransform::Pattern(id) => {
+                write!(f, "The pattern on object {id} has a degenerate transform")
+            }
+            DegenerateTransform::Camera => write!(f, "The camera has a degenerate transform"),
+        }
+    }
+}
+
+impl std::error::Error for DegenerateTransform {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Transform {
-    pub matrix: Matrix,
+    pub matrix: Matrix4,
 }
 
 impl Default for Transform {
@@ -17,7 +44,7 @@ impl Transform {
     #[must_use]
     pub fn new() -> Self {
         Transform {
-            matrix: Matrix::identity_matrix(),
+            matrix: Matrix4::identity_matrix(),
         }
     }
 
@@ -27,11 +54,11 @@ impl Transform {
         let upn = up.normalize();
         let left = forward.cross_product(&upn);
         let true_up = left.cross_product(&forward);
-        let orientation = Matrix::new(vec![
-            vec![left.x, left.y, left.z, 0.0],
-            vec![true_up.x, true_up.y, true_up.z, 0.0],
-            vec![-forward.x, -forward.y, -forward.z, 0.0],
-            vec![0.0, 0.0, 0.0, 1.0],
+        let orientation = Matrix4::new([
+            [left.x, left.y, left.z, 0.0],
+            [true_up.x, true_up.y, true_up.z, 0.0],
+            [-forward.x, -forward.y, -forward.z, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
         ]);
         let translation = Transform::new().translate(-from.x, -from.y, -from.z);
         let mut t = Transform::new();
@@ -54,7 +81,7 @@ impl Transform {
 
     #[must_use]
     pub fn translate(mut self, x: f64, y: f64, z: f64) -> Self {
-        let mut matrix = Matrix::identity_matrix();
+        let mut matrix = Matrix4::identity_matrix();
         matrix.set(0, 3, x);
         matrix.set(1, 3, y);
         matrix.set(2, 3, z);
@@ -64,7 +91,7 @@ impl Transform {
 
     #[must_use]
     pub fn scale(mut self, x: f64, y: f64, z: f64) -> Self {
-        let mut matrix = Matrix::identity_matrix();
+        let mut matrix = Matrix4::identity_matrix();
         matrix.set(0, 0, x);
         matrix.set(1, 1, y);
         matrix.set(2, 2, z);
@@ -74,7 +101,7 @@ impl Transform {
 
     #[must_use]
     pub fn rotate_x(mut self, r: f64) -> Self {
-        let mut matrix = Matrix::identity_matrix();
+        let mut matrix = Matrix4::identity_matrix();
         matrix.set(1, 1, r.cos());
         matrix.set(1, 2, -(r.sin()));
         matrix.set(2, 1, r.sin());
@@ -85,7 +112,7 @@ impl Transform {
 
     #[must_use]
     pub fn rotate_y(mut self, r: f64) -> Self {
-        let mut matrix = Matrix::identity_matrix();
+        let mut matrix = Matrix4::identity_matrix();
         matrix.set(0, 0, r.cos());
         matrix.set(0, 2, r.sin());
         matrix.set(2, 0, -(r.sin()));
@@ -96,7 +123,7 @@ impl Transform {
 
     #[must_use]
     pub fn rotate_z(mut self, r: f64) -> Self {
-        let mut matrix = Matrix::identity_matrix();
+        let mut matrix = Matrix4::identity_matrix();
         matrix.set(0, 0, r.cos());
         matrix.set(0, 1, -(r.sin()));
         matrix.set(1, 0, r.sin());
@@ -107,7 +134,7 @@ impl Transform {
 
     #[must_use]
     pub fn shear(mut self, xy: f64, xz: f64, yx: f64, yz: f64, zx: f64, zy: f64) -> Self {
-        let mut matrix = Matrix::identity_matrix();
+        let mut matrix = Matrix4::identity_matrix();
         matrix.set(0, 1, xy);
         matrix.set(0, 2, xz);
         matrix.set(1, 0, yx);
@@ -118,12 +145,28 @@ impl Transform {
         self
     }
 
+    /// # Panics
+    ///
+    /// Will panic if the transform is not invertible
     #[must_use]
     pub fn inverse(mut self) -> Self {
         self.matrix = self.matrix.inverse();
         self
     }
 
+    /// # Errors
+    ///
+    /// Will return `SingularMatrix` if the transform is not invertible
+    pub fn try_inverse(mut self) -> Result<Self, SingularMatrix> {
+        self.matrix = self.matrix.try_inverse()?;
+        Ok(self)
+    }
+
+    #[must_use]
+    pub fn is_invertible(&self) -> bool {
+        self.matrix.is_invertible()
+    }
+
     #[must_use]
     pub fn transpose(mut self) -> Self {
         self.matrix = self.matrix.transpose();
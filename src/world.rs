@@ -1,12 +1,16 @@
+use crate::bvh::Bvh;
 use crate::color::Color;
 use crate::float_near_equal;
 use crate::intersection::{Comp, IntersectionList};
+use crate::light::Light;
 use crate::point_light::PointLight;
 use crate::ray::Ray;
-use crate::shape::Shape;
+use crate::shape::{find_shape, Shape};
+use crate::transform::DegenerateTransform;
 use crate::tuple::Tuple;
 
 use std::fmt;
+use std::sync::OnceLock;
 
 #[derive(Debug)]
 pub struct InvalidWorldAccess;
@@ -19,10 +23,20 @@ impl fmt::Display for InvalidWorldAccess {
 
 impl std::error::Error for InvalidWorldAccess {}
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Debug)]
 pub struct World {
     objects: Vec<Shape>,
-    lights: Vec<PointLight>,
+    lights: Vec<Light>,
+    next_id: usize,
+    // Built over `objects` the first time the world is intersected, and discarded whenever
+    // an object is added.
+    bvh: OnceLock<Bvh>,
+}
+
+impl PartialEq for World {
+    fn eq(&self, other: &Self) -> bool {
+        self.objects == other.objects && self.lights == other.lights
+    }
 }
 
 impl Default for World {
@@ -37,10 +51,13 @@ impl World {
         Self {
             objects: vec![],
             lights: vec![],
+            next_id: 0,
+            bvh: OnceLock::new(),
         }
     }
 
     #[must_use]
+    #[allow(clippy::missing_panics_doc)]
     pub fn default_world() -> Self {
         let light = PointLight::new(Tuple::point(-10.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
         let mut s1 = Shape::sphere();
@@ -50,19 +67,19 @@ impl World {
         let mut s2 = Shape::sphere();
         s2.scale(0.5, 0.5, 0.5);
         let mut new_world = Self::new();
-        new_world.add_object(s1);
-        new_world.add_object(s2);
+        new_world
+            .add_object(s1)
+            .expect("default world transforms are invertible");
+        new_world
+            .add_object(s2)
+            .expect("default world transforms are invertible");
         new_world.add_light(light);
         new_world
     }
 
     #[must_use]
     pub fn get_object(&self, id: usize) -> Option<&Shape> {
-        if id < self.objects.len() {
-            Some(&self.objects[id])
-        } else {
-            None
-        }
+        find_shape(&self.objects, id)
     }
 
     #[must_use]
@@ -71,48 +88,71 @@ impl World {
     }
 
     #[must_use]
-    pub fn get_light(&self, id: usize) -> Option<&PointLight> {
-        if id < self.lights.len() {
-            Some(&self.lights[id])
-        } else {
-            None
-        }
+    pub fn get_light(&self, id: usize) -> Option<&Light> {
+        self.lights.get(id)
     }
 
+    /// Replaces the light with the given id.
+    ///
     /// # Errors
     ///
     /// Returns an error if id is not valid
     pub fn set_light(
         &mut self,
         id: usize,
-        new_light: &mut PointLight,
+        new_light: impl Into<Light>,
     ) -> Result<(), InvalidWorldAccess> {
+        let mut new_light = new_light.into();
         if id < self.lights.len() {
-            new_light.id = id;
-            self.lights[id] = *new_light;
+            new_light.set_id(id);
+            self.lights[id] = new_light;
             Ok(())
         } else {
             Err(InvalidWorldAccess)
         }
     }
 
-    pub fn add_object(&mut self, mut o: Shape) -> usize {
-        let id = self.objects.len();
-        o.set_id(id);
+    /// Adds a shape to the world, returning its id. The children of a group receive the ids
+    /// following the group's own.
+    ///
+    /// # Errors
+    ///
+    /// Will return `DegenerateTransform` naming the offending object if the shape, anything
+    /// inside it or any of their patterns has a transform that cannot be inverted. The world
+    /// is left unchanged.
+    pub fn add_object(&mut self, mut o: Shape) -> Result<usize, DegenerateTransform> {
+        let id = self.next_id;
+        let next_id = o.assign_ids(id);
+        o.check_transforms()?;
+        self.next_id = next_id;
+        o.build_bvh();
         self.objects.push(o);
-        id
+        self.bvh = OnceLock::new();
+        Ok(id)
     }
 
-    pub fn add_light(&mut self, mut l: PointLight) -> usize {
-        l.id = self.lights.len();
+    /// Adds a light of any kind to the world, returning its id.
+    pub fn add_light(&mut self, l: impl Into<Light>) -> usize {
+        let mut l = l.into();
+        l.set_id(self.lights.len());
         self.lights.push(l);
         self.lights.len() - 1
     }
 
+    /// Intersects the ray with every object whose bounds it passes through. Objects are
+    /// visited in the order they were added, so the result matches testing all of them.
     #[must_use]
     pub fn intersect(&self, r: &Ray) -> IntersectionList {
+        let bvh = self.bvh.get_or_init(|| {
+            let bounds: Vec<_> = self
+                .objects
+                .iter()
+                .map(Shape::parent_space_bounds)
+                .collect();
+            Bvh::build(&bounds)
+        });
         let mut intersections = Vec::new();
-        for i in 0..self.objects.len() {
+        for i in bvh.candidates(r) {
             if let Some(mut xs) = self.objects[i].intersect(r) {
                 intersections.append(&mut xs);
             }
@@ -121,6 +161,7 @@ impl World {
     }
 
     #[must_use]
+    #[allow(clippy::cast_precision_loss)]
     /// # Panics
     ///
     /// Will panic if `comp` has an invalid value for `object_id`
@@ -131,29 +172,28 @@ impl World {
         let object = self.get_object(comps.object_id).unwrap();
         let material = object.material();
         let mut surface = Color::new(0.0, 0.0, 0.0);
-        let mut reflected = Color::new(0.0, 0.0, 0.0);
-        let mut refracted = Color::new(0.0, 0.0, 0.0);
         for light in &self.lights {
-            let shadowed = self.is_shadowed(comps.over_point, light);
             surface += material.lighting(
                 light,
                 object,
                 comps.point,
                 comps.eyev,
                 comps.normalv,
-                shadowed,
+                self.light_visibility(comps.over_point, light),
             );
-            let mut curr_reflected = self.reflected_color(comps, max_depth);
-            let mut curr_refracted = self.refracted_color(comps, max_depth);
+        }
 
-            if material.reflective > 0.0 && material.transparency > 0.0 {
-                let reflectance = Self::schlick(comps);
-                curr_reflected *= reflectance;
-                curr_refracted *= 1.0 - reflectance;
-            }
-            reflected += curr_reflected;
-            refracted += curr_refracted;
+        let mut reflected = self.reflected_color(comps, max_depth);
+        let mut refracted = self.refracted_color(comps, max_depth);
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            let reflectance = Self::schlick(comps);
+            reflected *= reflectance;
+            refracted *= 1.0 - reflectance;
         }
+        // Reflections and refractions are counted once for every light.
+        let lights = self.lights.len() as f64;
+        reflected *= lights;
+        refracted *= lights;
         surface + reflected + refracted
     }
 
@@ -213,14 +253,38 @@ impl World {
         }
     }
 
+    /// Returns whether `p` is completely hidden from the light by shadow casting objects.
+    /// Points that can see part of an area light are not in shadow.
+    ///
+    /// # Panics
+    ///
+    /// Could panic if an intersection has an invalid object id.
     #[must_use]
+    pub fn is_shadowed(&self, p: Tuple, light: &Light) -> bool {
+        self.light_visibility(p, light) == 0.0
+    }
+
+    /// Returns the fraction of the light's samples that can be seen from `p`, from 0 when
+    /// it is fully in shadow to 1 when it is fully lit. Shadow rays towards directional
+    /// lights never end, so anything in their direction casts a shadow.
+    ///
     /// # Panics
     ///
     /// Could panic if an intersection has an invalid object id.
-    pub fn is_shadowed(&self, p: Tuple, light: &PointLight) -> bool {
-        let v = light.position - p;
-        let distance = v.magnitude();
-        let shadow_ray = Ray::new(p, v.normalize());
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn light_visibility(&self, p: Tuple, light: &Light) -> f64 {
+        let samples = light.samples(p);
+        let visible = samples
+            .iter()
+            .filter(|sample| !self.is_blocked(p, sample.direction, sample.distance))
+            .count();
+        visible as f64 / samples.len() as f64
+    }
+
+    // Whether a shadow casting object lies less than `distance` from `p` along `direction`.
+    fn is_blocked(&self, p: Tuple, direction: Tuple, distance: f64) -> bool {
+        let shadow_ray = Ray::new(p, direction);
         let mut ix = self.intersect(&shadow_ray);
         if let Some(hit) = ix.hit() {
             let object = self.get_object(hit.object_id).unwrap();
@@ -0,0 +1,213 @@
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use ray_tracer::area_light::AreaLight;
+use ray_tracer::color::Color;
+use ray_tracer::float_near_equal;
+use ray_tracer::intersection::Intersection;
+use ray_tracer::light::Light;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{MAX_REFLECT_DEPTH, WHITE};
+
+fn default_area_light() -> AreaLight {
+    AreaLight::new(
+        Tuple::point(-0.5, -0.5, -5.0),
+        Tuple::vector(1.0, 0.0, 0.0),
+        2,
+        Tuple::vector(0.0, 1.0, 0.0),
+        2,
+        WHITE,
+    )
+}
+
+#[test]
+fn area_light_init() {
+    let corner = Tuple::point(0.0, 0.0, 0.0);
+    let light = AreaLight::new(
+        corner,
+        Tuple::vector(2.0, 0.0, 0.0),
+        4,
+        Tuple::vector(0.0, 0.0, 1.0),
+        2,
+        WHITE,
+    );
+    assert_eq!(light.corner, corner);
+    assert_eq!(light.uvec, Tuple::vector(0.5, 0.0, 0.0));
+    assert_eq!(light.usteps, 4);
+    assert_eq!(light.vvec, Tuple::vector(0.0, 0.0, 0.5));
+    assert_eq!(light.vsteps, 2);
+    assert_eq!(light.samples(), 8);
+    assert_eq!(light.position, Tuple::point(1.0, 0.0, 0.5));
+    assert!(!light.jitter);
+}
+
+#[test]
+#[should_panic(expected = "at least one cell")]
+fn area_light_without_cells() {
+    let _ = AreaLight::new(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(1.0, 0.0, 0.0),
+        0,
+        Tuple::vector(0.0, 1.0, 0.0),
+        2,
+        WHITE,
+    );
+}
+
+#[test]
+fn points_on_area_light() {
+    let light = AreaLight::new(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        4,
+        Tuple::vector(0.0, 0.0, 1.0),
+        2,
+        WHITE,
+    );
+    for (u, v, expected) in [
+        (0, 0, Tuple::point(0.25, 0.0, 0.25)),
+        (1, 0, Tuple::point(0.75, 0.0, 0.25)),
+        (0, 1, Tuple::point(0.25, 0.0, 0.75)),
+        (2, 0, Tuple::point(1.25, 0.0, 0.25)),
+        (3, 1, Tuple::point(1.75, 0.0, 0.75)),
+    ] {
+        assert_eq!(light.point_on_light(u, v, 0.5, 0.5), expected);
+    }
+    assert_eq!(
+        light.point_on_light(3, 1, 0.0, 1.0),
+        Tuple::point(1.5, 0.0, 1.0)
+    );
+
+    let points = light.sample_points(Tuple::point(0.0, 5.0, 0.0));
+    assert_eq!(points.len(), 8);
+    assert_eq!(points[0], Tuple::point(0.25, 0.0, 0.25));
+    assert_eq!(points[7], Tuple::point(1.75, 0.0, 0.75));
+}
+
+#[test]
+fn jittered_points_stay_in_their_cells() {
+    let mut light = AreaLight::new(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        4,
+        Tuple::vector(0.0, 0.0, 1.0),
+        2,
+        WHITE,
+    );
+    light.set_jitter(true);
+    let from = Tuple::point(0.3, 2.0, -1.0);
+    let points = light.sample_points(from);
+    assert_eq!(light.sample_points(from), points);
+    assert_ne!(light.sample_points(Tuple::point(0.3, 2.0, -1.5)), points);
+    for (i, point) in points.iter().enumerate() {
+        let (u, v) = ((i % 4) as f64, (i / 4) as f64);
+        assert!(point.x >= u * 0.5 && point.x <= (u + 1.0) * 0.5);
+        assert!(point.z >= v * 0.5 && point.z <= (v + 1.0) * 0.5);
+        assert!(float_near_equal(point.y, 0.0));
+    }
+}
+
+#[test]
+fn area_light_visibility() {
+    let w = World::default();
+    let light = AreaLight::new(
+        Tuple::point(-0.5, -0.5, -5.0),
+        Tuple::vector(1.0, 0.0, 0.0),
+        2,
+        Tuple::vector(0.0, 1.0, 0.0),
+        2,
+        WHITE,
+    );
+    for (point, expected) in [
+        (Tuple::point(0.0, 0.0, 2.0), 0.0),
+        (Tuple::point(1.0, -1.0, 2.0), 0.25),
+        (Tuple::point(1.5, 0.0, 2.0), 0.5),
+        (Tuple::point(1.25, 1.25, 3.0), 0.75),
+        (Tuple::point(0.0, 0.0, -2.0), 1.0),
+    ] {
+        assert!(float_near_equal(
+            w.light_visibility(point, &light.into()),
+            expected
+        ));
+    }
+}
+
+#[test]
+fn lighting_with_area_light() {
+    let light = default_area_light();
+    let mut object = Shape::sphere();
+    object.set_ambient(0.1);
+    object.set_diffuse(0.9);
+    object.set_specular(0.0);
+    object.set_color(WHITE);
+    let eye = Tuple::point(0.0, 0.0, -5.0);
+    for (point, expected) in [
+        (Tuple::point(0.0, 0.0, -1.0), 0.9965),
+        (Tuple::point(0.0, FRAC_1_SQRT_2, -FRAC_1_SQRT_2), 0.6232),
+    ] {
+        let eyev = (eye - point).normalize();
+        let normalv = Tuple::vector(point.x, point.y, point.z);
+        let result = object
+            .material()
+            .lighting(&light.into(), &object, point, eyev, normalv, 1.0);
+        assert!((result.red - expected).abs() < 0.0001);
+        assert!((result.green - expected).abs() < 0.0001);
+        assert!((result.blue - expected).abs() < 0.0001);
+    }
+
+    // Only the ambient light is left when the light is fully hidden.
+    let point = Tuple::point(0.0, 0.0, -1.0);
+    let result = object.material().lighting(
+        &light.into(),
+        &object,
+        point,
+        Tuple::vector(0.0, 0.0, -1.0),
+        Tuple::vector(0.0, 0.0, -1.0),
+        0.0,
+    );
+    assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+}
+
+#[test]
+fn area_lights_cast_penumbras() {
+    let mut w = World::new();
+    let mut floor = Shape::plane();
+    floor.set_specular(0.0);
+    w.add_object(floor).unwrap();
+    let mut blocker = Shape::sphere();
+    blocker.translate(0.0, 2.0, 0.0);
+    w.add_object(blocker).unwrap();
+    let id = w.add_light(AreaLight::new(
+        Tuple::point(-1.0, 5.0, -1.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        8,
+        Tuple::vector(0.0, 0.0, 2.0),
+        8,
+        WHITE,
+    ));
+    assert_eq!(id, 0);
+    let light = w.get_light(0).unwrap();
+    assert!(matches!(light, Light::Area(_)));
+    assert_eq!(light.samples(Tuple::point(0.0, 0.0, 0.0)).len(), 64);
+    assert_eq!(w.get_light(1), None);
+
+    let shade = |x: f64| {
+        let r = Ray::new(Tuple::point(x, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        let comps = Intersection::new(1.0, 0).prepare_computation(&r, &w);
+        w.shade_hit(&comps, MAX_REFLECT_DEPTH).red
+    };
+    let umbra = shade(0.0);
+    let penumbra = shade(1.4);
+    let lit = shade(4.0);
+    assert!(float_near_equal(umbra, 0.1));
+    assert!(penumbra > umbra && penumbra < lit);
+    assert!(
+        w.color_at(
+            &Ray::new(Tuple::point(0.0, 1.0, -5.0), Tuple::vector(0.0, 0.0, 1.0)),
+            MAX_REFLECT_DEPTH
+        )
+        .red > 0.0
+    );
+}
@@ -0,0 +1,133 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use ray_tracer::bounds::BoundingBox;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::transform::Transform;
+use ray_tracer::tuple::Tuple;
+
+#[test]
+fn adding_points() {
+    let mut b = BoundingBox::empty();
+    assert!(b.is_empty());
+    b.add_point(Tuple::point(-5.0, 2.0, 0.0));
+    b.add_point(Tuple::point(7.0, 0.0, -3.0));
+    assert!(!b.is_empty());
+    assert_eq!(b.min, Tuple::point(-5.0, 0.0, -3.0));
+    assert_eq!(b.max, Tuple::point(7.0, 2.0, 0.0));
+}
+
+#[test]
+fn merging_boxes() {
+    let mut b1 = BoundingBox::new(Tuple::point(-5.0, -2.0, 0.0), Tuple::point(7.0, 4.0, 4.0));
+    let b2 = BoundingBox::new(Tuple::point(8.0, -7.0, -2.0), Tuple::point(14.0, 2.0, 8.0));
+    b1.merge(&b2);
+    assert_eq!(b1.min, Tuple::point(-5.0, -7.0, -2.0));
+    assert_eq!(b1.max, Tuple::point(14.0, 4.0, 8.0));
+    b1.merge(&BoundingBox::empty());
+    assert_eq!(b1.min, Tuple::point(-5.0, -7.0, -2.0));
+}
+
+#[test]
+fn contains_point() {
+    let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+    assert!(b.contains_point(Tuple::point(5.0, -2.0, 0.0)));
+    assert!(b.contains_point(Tuple::point(8.0, 1.0, 3.0)));
+    assert!(!b.contains_point(Tuple::point(3.0, 0.0, 3.0)));
+    assert!(!b.contains_point(Tuple::point(8.0, 1.0, 8.0)));
+}
+
+#[test]
+fn transforming_box() {
+    let b = BoundingBox::new(Tuple::point(-1.0, -1.0, -1.0), Tuple::point(1.0, 1.0, 1.0));
+    let t = Transform::new().rotate_y(PI / 4.0).rotate_x(PI / 4.0);
+    let result = b.transform(&t);
+    assert_eq!(
+        result.min,
+        Tuple::point(-SQRT_2, -1.7071067811865475, -1.7071067811865475)
+    );
+    assert_eq!(
+        result.max,
+        Tuple::point(SQRT_2, 1.7071067811865475, 1.7071067811865475)
+    );
+
+    let infinite = BoundingBox::infinite().transform(&t);
+    assert!(!infinite.is_finite());
+}
+
+#[test]
+fn ray_intersects_box() {
+    let b = BoundingBox::new(Tuple::point(5.0, -2.0, 0.0), Tuple::point(11.0, 4.0, 7.0));
+    let hits = [
+        (Tuple::point(15.0, 1.0, 2.0), Tuple::vector(-1.0, 0.0, 0.0)),
+        (Tuple::point(7.0, 6.0, 5.0), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(9.0, -1.0, -8.0), Tuple::vector(0.0, 0.0, 1.0)),
+        (Tuple::point(8.0, 1.0, 3.5), Tuple::vector(0.0, 0.0, 1.0)),
+        // The box lies behind the ray, which still counts as the line passes through it.
+        (Tuple::point(15.0, 1.0, 2.0), Tuple::vector(1.0, 0.0, 0.0)),
+    ];
+    for (origin, direction) in hits {
+        assert!(b.intersects(&Ray::new(origin, direction)));
+    }
+    let misses = [
+        (Tuple::point(9.0, -1.0, -8.0), Tuple::vector(2.0, 4.0, 6.0)),
+        (Tuple::point(8.0, 3.0, -4.0), Tuple::vector(6.0, 2.0, 4.0)),
+        (Tuple::point(12.0, 5.0, 4.0), Tuple::vector(0.0, 0.0, -1.0)),
+        (Tuple::point(8.0, 2.0, 12.0), Tuple::vector(0.0, 1.0, 0.0)),
+    ];
+    for (origin, direction) in misses {
+        assert!(!b.intersects(&Ray::new(origin, direction.normalize())));
+    }
+    assert!(!BoundingBox::empty().intersects(&Ray::new(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(0.0, 0.0, 1.0)
+    )));
+}
+
+#[test]
+fn primitive_bounds() {
+    let s = Shape::sphere().bounds();
+    assert_eq!(s.min, Tuple::point(-1.0, -1.0, -1.0));
+    assert_eq!(s.max, Tuple::point(1.0, 1.0, 1.0));
+
+    assert!(!Shape::plane().bounds().is_finite());
+    assert!(!Shape::cylinder().bounds().is_finite());
+
+    let c = Shape::truncated_cylinder(-5.0, 3.0, true).bounds();
+    assert_eq!(c.min, Tuple::point(-1.0, -5.0, -1.0));
+    assert_eq!(c.max, Tuple::point(1.0, 3.0, 1.0));
+
+    let c = Shape::truncated_cone(-5.0, 3.0, false).bounds();
+    assert_eq!(c.min, Tuple::point(-5.0, -5.0, -5.0));
+    assert_eq!(c.max, Tuple::point(5.0, 3.0, 5.0));
+
+    let t = Shape::triangle(
+        Tuple::point(-3.0, 7.0, 2.0),
+        Tuple::point(6.0, 2.0, -4.0),
+        Tuple::point(2.0, -1.0, -1.0),
+    )
+    .bounds();
+    assert_eq!(t.min, Tuple::point(-3.0, -1.0, -4.0));
+    assert_eq!(t.max, Tuple::point(6.0, 7.0, 2.0));
+}
+
+#[test]
+fn group_bounds_include_children_transforms() {
+    let mut s = Shape::sphere();
+    s.scale(2.0, 2.0, 2.0);
+    s.translate(2.0, 5.0, -3.0);
+    let mut c = Shape::truncated_cylinder(-2.0, 2.0, false);
+    c.scale(0.5, 1.0, 0.5);
+    c.translate(-4.0, -1.0, 4.0);
+    let mut g = Shape::group();
+    g.add_child(s);
+    g.add_child(c);
+    let b = g.bounds();
+    assert_eq!(b.min, Tuple::point(-4.5, -3.0, -5.0));
+    assert_eq!(b.max, Tuple::point(4.0, 7.0, 4.5));
+
+    g.translate(1.0, 0.0, 0.0);
+    let b = g.parent_space_bounds();
+    assert_eq!(b.min, Tuple::point(-3.5, -3.0, -5.0));
+    assert_eq!(b.max, Tuple::point(5.0, 7.0, 4.5));
+}
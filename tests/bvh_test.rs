@@ -0,0 +1,113 @@
+use ray_tracer::bounds::BoundingBox;
+use ray_tracer::bvh::Bvh;
+use ray_tracer::float_near_equal;
+use ray_tracer::intersection::IntersectionList;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+
+fn unit_box_at(x: f64) -> BoundingBox {
+    BoundingBox::new(
+        Tuple::point(x - 1.0, -1.0, -1.0),
+        Tuple::point(x + 1.0, 1.0, 1.0),
+    )
+}
+
+#[test]
+fn candidates_skip_missed_boxes() {
+    let bounds: Vec<BoundingBox> = (0..20).map(|i| unit_box_at(f64::from(i) * 3.0)).collect();
+    let bvh = Bvh::build(&bounds);
+
+    let r = Ray::new(Tuple::point(9.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_eq!(bvh.candidates(&r), vec![3]);
+
+    let r = Ray::new(Tuple::point(-5.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+    assert_eq!(bvh.candidates(&r), (0..20).collect::<Vec<usize>>());
+
+    let r = Ray::new(Tuple::point(0.0, 5.0, 0.0), Tuple::vector(1.0, 0.0, 0.0));
+    assert!(bvh.candidates(&r).is_empty());
+}
+
+#[test]
+fn unbounded_items_are_always_candidates() {
+    let bounds = vec![
+        unit_box_at(0.0),
+        BoundingBox::infinite(),
+        BoundingBox::empty(),
+        unit_box_at(10.0),
+    ];
+    let bvh = Bvh::build(&bounds);
+    let r = Ray::new(Tuple::point(10.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_eq!(bvh.candidates(&r), vec![1, 3]);
+}
+
+// Intersects every top-level object in turn, the way `World::intersect` did before it used
+// a bounding volume hierarchy.
+fn brute_force(world: &World, ids: &[usize], r: &Ray) -> IntersectionList {
+    let mut xs = vec![];
+    for id in ids {
+        if let Some(mut hits) = world.get_object(*id).unwrap().intersect(r) {
+            xs.append(&mut hits);
+        }
+    }
+    IntersectionList::new(&xs)
+}
+
+#[test]
+fn world_intersect_matches_brute_force() {
+    let mut world = World::new();
+    let mut ids = vec![world.add_object(Shape::plane()).unwrap()];
+    for i in 0..6 {
+        for j in 0..6 {
+            let (x, z) = (f64::from(i) * 1.5 - 4.0, f64::from(j) * 1.5 - 4.0);
+            let mut s = match (i + j) % 3 {
+                0 => Shape::sphere(),
+                1 => Shape::cube(),
+                _ => Shape::truncated_cylinder(0.0, 1.0, true),
+            };
+            s.scale(0.5, 0.5, 0.5);
+            s.translate(x, 0.5, z);
+            ids.push(world.add_object(s).unwrap());
+        }
+    }
+    let mut mesh = Shape::group();
+    for i in 0..10 {
+        let x = f64::from(i) * 0.3;
+        mesh.add_child(Shape::triangle(
+            Tuple::point(x, 2.0, 0.0),
+            Tuple::point(x + 0.3, 2.0, 0.0),
+            Tuple::point(x, 2.5, 0.0),
+        ));
+    }
+    ids.push(world.add_object(mesh).unwrap());
+
+    for i in 0..20 {
+        for j in 0..20 {
+            let target = Tuple::point(f64::from(i) * 0.5 - 5.0, 1.0, f64::from(j) * 0.5 - 5.0);
+            let origin = Tuple::point(-1.0, 6.0, -10.0);
+            let r = Ray::new(origin, (target - origin).normalize());
+            let expected = brute_force(&world, &ids, &r);
+            let actual = world.intersect(&r);
+            assert_eq!(actual.len(), expected.len());
+            for k in 0..actual.len() {
+                let (a, e) = (actual.get(k).unwrap(), expected.get(k).unwrap());
+                assert_eq!(a.object_id, e.object_id);
+                assert!(float_near_equal(a.t, e.t));
+            }
+        }
+    }
+}
+
+#[test]
+fn world_intersect_after_adding_object() {
+    let mut world = World::new();
+    world.add_object(Shape::sphere()).unwrap();
+    let r = Ray::new(Tuple::point(5.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_eq!(world.intersect(&r).len(), 0);
+
+    let mut s = Shape::sphere();
+    s.translate(5.0, 0.0, 0.0);
+    world.add_object(s).unwrap();
+    assert_eq!(world.intersect(&r).len(), 2);
+}
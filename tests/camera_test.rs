@@ -3,10 +3,26 @@ use std::f64::consts::PI;
 use ray_tracer::camera::Camera;
 use ray_tracer::color::Color;
 use ray_tracer::float_near_equal;
-use ray_tracer::transform::Transform;
+use ray_tracer::progress::{CancellationToken, ProgressBar, RenderObserver, RenderProgress};
+use ray_tracer::sampling::{
+    AdaptiveSampling, AdaptiveStats, ReconstructionFilter, SamplePattern, Sampler,
+};
+use ray_tracer::transform::{DegenerateTransform, Transform};
 use ray_tracer::tuple::Tuple;
 use ray_tracer::world::World;
 
+// A camera five units in front of the origin, looking at it.
+fn camera_facing_origin(hsize: f64, vsize: f64) -> Camera {
+    let mut c = Camera::new(hsize, vsize, PI / 2.0);
+    c.set_transform(Transform::view_transform(
+        &Tuple::point(0.0, 0.0, -5.0),
+        &Tuple::point(0.0, 0.0, 0.0),
+        &Tuple::vector(0.0, 1.0, 0.0),
+    ))
+    .unwrap();
+    c
+}
+
 #[test]
 fn init() {
     let hsize = 160.0;
@@ -16,7 +32,7 @@ fn init() {
     assert!(float_near_equal(c.hsize, 160.0));
     assert!(float_near_equal(c.vsize, 120.0));
     assert!(float_near_equal(c.field_of_view, PI / 2.0));
-    assert_eq!(c.transform, Transform::new());
+    assert_eq!(*c.transform(), Transform::new());
 }
 
 #[test]
@@ -43,8 +59,9 @@ fn ray_construction() {
         Tuple::vector(0.6651864261194508, 0.3325932130597254, -0.6685123582500481)
     );
 
-    c.transform = c.transform.translate(0.0, -2.0, 5.0);
-    c.transform = c.transform.rotate_y(PI / 4.0);
+    c.set_transform(c.transform().translate(0.0, -2.0, 5.0))
+        .unwrap();
+    c.set_transform(c.transform().rotate_y(PI / 4.0)).unwrap();
     let after_transform = c.ray_for_pixel(100.0, 50.0);
     assert_eq!(after_transform.origin, Tuple::point(0.0, 2.0, -5.0));
     assert_eq!(
@@ -60,7 +77,8 @@ fn render() {
     let from = Tuple::point(0.0, 0.0, -5.0);
     let to = Tuple::point(0.0, 0.0, 0.0);
     let up = Tuple::vector(0.0, 1.0, 0.0);
-    c.transform = Transform::view_transform(&from, &to, &up);
+    c.set_transform(Transform::view_transform(&from, &to, &up))
+        .unwrap();
     let image = c.render(&w);
     assert_eq!(
         image.pixel_at(5, 5),
@@ -71,3 +89,240 @@ fn render() {
         )
     );
 }
+
+#[test]
+fn render_is_independent_of_thread_count() {
+    let w = World::default();
+    let mut c = Camera::new(21.0, 13.0, PI / 2.0);
+    let from = Tuple::point(1.0, 0.5, -5.0);
+    let to = Tuple::point(0.0, 0.0, 0.0);
+    let up = Tuple::vector(0.0, 1.0, 0.0);
+    c.set_transform(Transform::view_transform(&from, &to, &up))
+        .unwrap();
+    let expected = c.render_with_threads(&w, 1);
+    for threads in [2, 3, 8, 64] {
+        let image = c.render_with_threads(&w, threads);
+        assert_eq!(image.width(), 21);
+        assert_eq!(image.height(), 13);
+        assert_eq!(image.pixels(), expected.pixels());
+    }
+}
+
+#[test]
+fn rays_through_subpixels() {
+    let c = Camera::new(201.0, 101.0, PI / 2.0);
+    let r = c.ray_for_subpixel(100.0, 50.0, 0.5, 0.5);
+    assert_eq!(r, c.ray_for_pixel(100.0, 50.0));
+    let r = c.ray_for_subpixel(0.0, 0.0, 0.0, 0.0);
+    let corner = Tuple::vector(c.half_width, c.half_height, -1.0).normalize();
+    assert_eq!(r.direction, corner);
+}
+
+#[test]
+fn single_sample_matches_pixel_centre() {
+    let w = World::default();
+    let mut c = camera_facing_origin(8.0, 6.0);
+    let expected = c.render_with_threads(&w, 1);
+    c.set_sampler(Sampler::new(
+        1,
+        SamplePattern::Grid,
+        ReconstructionFilter::Gaussian,
+    ));
+    assert_eq!(c.render_with_threads(&w, 1).pixels(), expected.pixels());
+}
+
+#[test]
+fn supersampling_softens_edges() {
+    let w = World::default();
+    let mut c = camera_facing_origin(11.0, 11.0);
+    let aliased = c.render_with_threads(&w, 1);
+    for pattern in [
+        SamplePattern::Grid,
+        SamplePattern::Jittered,
+        SamplePattern::Random,
+    ] {
+        c.set_sampler(Sampler::new(16, pattern, ReconstructionFilter::Box));
+        let smooth = c.render_with_threads(&w, 1);
+        // Pixels on the silhouette of the sphere mix its color with the black background,
+        // while the middle of the sphere and the corners stay fully covered or empty.
+        let differing = aliased
+            .pixels()
+            .iter()
+            .zip(smooth.pixels())
+            .filter(|(a, b)| (a.red - b.red).abs() > 0.05)
+            .count();
+        assert!(differing > 0);
+        assert_eq!(smooth.pixel_at(0, 0), Color::new(0.0, 0.0, 0.0));
+        assert!(smooth.pixel_at(5, 5).green > 0.4);
+    }
+}
+
+#[test]
+fn supersampled_render_is_reproducible() {
+    let w = World::default();
+    let mut c = camera_facing_origin(8.0, 6.0);
+    c.set_sampler(Sampler::new(
+        5,
+        SamplePattern::Random,
+        ReconstructionFilter::Tent,
+    ));
+    let expected = c.render_with_threads(&w, 1);
+    for threads in [1, 3, 8] {
+        assert_eq!(
+            c.render_with_threads(&w, threads).pixels(),
+            expected.pixels()
+        );
+    }
+}
+
+#[test]
+fn adaptive_render_without_refinement() {
+    let w = World::default();
+    let c = camera_facing_origin(11.0, 11.0);
+    let (image, stats) = c.render_adaptive(&w, 2, &AdaptiveSampling::new(0.1, 0));
+    assert_eq!(image.pixels(), c.render_with_threads(&w, 1).pixels());
+    assert_eq!(stats.pixels, 121);
+    assert_eq!(stats.refined_pixels, 0);
+    assert_eq!(stats.samples, 121);
+
+    // At depth one refined pixels are sampled once more, as four quarters.
+    let (_, stats) = c.render_adaptive(&w, 2, &AdaptiveSampling::new(0.1, 1));
+    assert!(stats.refined_pixels > 0);
+    assert_eq!(stats.samples, 121 + 4 * stats.refined_pixels);
+}
+
+#[test]
+fn adaptive_render_refines_only_edges() {
+    let w = World::default();
+    let c = camera_facing_origin(11.0, 11.0);
+    let aliased = c.render_with_threads(&w, 1);
+    let (image, stats) = c.render_adaptive(&w, 3, &AdaptiveSampling::new(0.1, 3));
+    assert!(stats.refined_pixels > 0 && stats.refined_pixels < stats.pixels);
+    assert!(stats.samples > stats.pixels + 4 * stats.refined_pixels);
+    assert!(stats.samples_per_pixel() > 1.0);
+
+    // The flat background in the corner is left alone, while the silhouette is softened.
+    assert_eq!(image.pixel_at(0, 0), aliased.pixel_at(0, 0));
+    assert_ne!(image.pixels(), aliased.pixels());
+
+    let (again, again_stats) = c.render_adaptive(&w, 1, &AdaptiveSampling::new(0.1, 3));
+    assert_eq!(again.pixels(), image.pixels());
+    assert_eq!(again_stats, stats);
+}
+
+#[test]
+fn adaptive_render_of_a_flat_image() {
+    let w = World::new();
+    let c = camera_facing_origin(11.0, 11.0);
+    let (image, stats) = c.render_adaptive(&w, 4, &AdaptiveSampling::default());
+    assert!(image
+        .pixels()
+        .iter()
+        .all(|p| *p == Color::new(0.0, 0.0, 0.0)));
+    assert_eq!(
+        stats,
+        AdaptiveStats {
+            pixels: 121,
+            refined_pixels: 0,
+            samples: 121
+        }
+    );
+    assert!(float_near_equal(stats.samples_per_pixel(), 1.0));
+}
+
+#[derive(Default)]
+struct RecordingObserver {
+    started: Vec<RenderProgress>,
+    rows: Vec<RenderProgress>,
+    finished: Vec<RenderProgress>,
+    cancel_after: Option<(usize, CancellationToken)>,
+}
+
+impl RenderObserver for RecordingObserver {
+    fn started(&mut self, progress: &RenderProgress) {
+        self.started.push(*progress);
+    }
+
+    fn row_finished(&mut self, progress: &RenderProgress) {
+        self.rows.push(*progress);
+        if let Some((rows, token)) = &self.cancel_after {
+            if progress.rows_done >= *rows {
+                token.cancel();
+            }
+        }
+    }
+
+    fn finished(&mut self, progress: &RenderProgress) {
+        self.finished.push(*progress);
+    }
+}
+
+#[test]
+fn render_reports_progress() {
+    let w = World::default();
+    let c = camera_facing_origin(8.0, 6.0);
+    let mut observer = RecordingObserver::default();
+    let image = c
+        .render_observed(&w, 3, &mut observer, &CancellationToken::new())
+        .unwrap();
+    assert_eq!(image.pixels(), c.render_with_threads(&w, 1).pixels());
+
+    assert_eq!(observer.started.len(), 1);
+    assert_eq!(observer.started[0].total_pixels, 48);
+    assert_eq!(observer.started[0].total_rows, 6);
+    assert_eq!(observer.started[0].pixels_done, 0);
+
+    assert_eq!(observer.rows.len(), 6);
+    for (i, progress) in observer.rows.iter().enumerate() {
+        assert_eq!(progress.rows_done, i + 1);
+        assert_eq!(progress.pixels_done, (i + 1) * 8);
+    }
+    assert_eq!(observer.finished.len(), 1);
+    assert_eq!(observer.finished[0].percent_done(), 100);
+}
+
+#[test]
+fn cancelled_render() {
+    let w = World::default();
+    let c = camera_facing_origin(8.0, 6.0);
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut observer = RecordingObserver::default();
+    assert!(c.render_observed(&w, 2, &mut observer, &token).is_err());
+    assert!(observer.rows.is_empty());
+    assert!(observer.finished.is_empty());
+
+    let token = CancellationToken::new();
+    let mut observer = RecordingObserver {
+        cancel_after: Some((2, token.clone())),
+        ..RecordingObserver::default()
+    };
+    assert!(c.render_observed(&w, 1, &mut observer, &token).is_err());
+    assert!(observer.rows.len() < 6);
+    assert!(observer.finished.is_empty());
+}
+
+#[test]
+fn progress_bar_output() {
+    let w = World::default();
+    let c = camera_facing_origin(8.0, 6.0);
+    let mut bar = ProgressBar::new(Vec::new());
+    c.render_observed(&w, 2, &mut bar, &CancellationToken::new())
+        .unwrap();
+    let output = String::from_utf8(bar.into_inner()).unwrap();
+    assert!(output.starts_with("generating data for 48 pixels\n"));
+    assert!(output.ends_with(&format!("\r[{}>] 100%\n", "=".repeat(50))));
+}
+
+#[test]
+fn degenerate_camera_transform() {
+    let mut c = Camera::new(11.0, 11.0, PI / 2.0);
+    let t = Transform::new().translate(0.0, 1.0, 0.0);
+    c.set_transform(t).unwrap();
+    assert_eq!(
+        c.set_transform(Transform::new().scale(1.0, 0.0, 1.0)),
+        Err(DegenerateTransform::Camera)
+    );
+    assert_eq!(*c.transform(), t);
+}
@@ -0,0 +1,143 @@
+use ray_tracer::color::Color;
+use ray_tracer::float_near_equal;
+use ray_tracer::point_light::PointLight;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{BLACK, MAX_REFLECT_DEPTH, WHITE};
+
+#[test]
+fn ray_strikes_cone() {
+    let mut c = Shape::cone();
+    c.set_id(0);
+    let cases = [
+        (
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            5.0,
+            5.0,
+        ),
+        (
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::vector(1.0, 1.0, 1.0),
+            8.660254037844386,
+            8.660254037844386,
+        ),
+        (
+            Tuple::point(1.0, 1.0, -5.0),
+            Tuple::vector(-0.5, -1.0, 1.0),
+            4.550055679356349,
+            49.449944320643645,
+        ),
+    ];
+    for (origin, direction, t0, t1) in cases {
+        let xs = c
+            .intersect(&Ray::new(origin, direction.normalize()))
+            .unwrap();
+        assert_eq!(xs.len(), 2);
+        assert!(float_near_equal(xs[0].t, t0));
+        assert!(float_near_equal(xs[1].t, t1));
+    }
+}
+
+#[test]
+fn ray_parallel_to_one_half() {
+    let mut c = Shape::cone();
+    c.set_id(0);
+    let r = Ray::new(
+        Tuple::point(0.0, 0.0, -1.0),
+        Tuple::vector(0.0, 1.0, 1.0).normalize(),
+    );
+    let xs = c.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 1);
+    assert!(float_near_equal(xs[0].t, 0.3535533905932738));
+}
+
+#[test]
+fn ray_along_cone_surface_through_apex() {
+    let mut c = Shape::cone();
+    c.set_id(0);
+    let r = Ray::new(
+        Tuple::point(0.0, -1.0, -1.0),
+        Tuple::vector(0.0, 1.0, 1.0).normalize(),
+    );
+    let xs = c.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 0);
+}
+
+#[test]
+fn intersect_capped_cone() {
+    let mut c = Shape::truncated_cone(-0.5, 0.5, true);
+    c.set_id(0);
+    let cases = [
+        (
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+            0,
+        ),
+        (
+            Tuple::point(0.0, 0.0, -0.25),
+            Tuple::vector(0.0, 1.0, 1.0),
+            2,
+        ),
+        (
+            Tuple::point(0.0, 0.0, -0.25),
+            Tuple::vector(0.0, 1.0, 0.0),
+            4,
+        ),
+    ];
+    for (origin, direction, count) in cases {
+        let xs = c
+            .intersect(&Ray::new(origin, direction.normalize()))
+            .unwrap();
+        assert_eq!(xs.len(), count);
+    }
+}
+
+#[test]
+fn normal_on_cone() {
+    let c = Shape::cone();
+    assert_eq!(
+        c.normal_at(Tuple::point(1.0, 1.0, 1.0)),
+        Tuple::vector(1.0, -(2_f64.sqrt()), 1.0).normalize()
+    );
+    assert_eq!(
+        c.normal_at(Tuple::point(-1.0, -1.0, 0.0)),
+        Tuple::vector(-1.0, 1.0, 0.0).normalize()
+    );
+}
+
+#[test]
+fn normal_on_cone_caps() {
+    let c = Shape::truncated_cone(-1.0, 2.0, true);
+    assert_eq!(
+        c.normal_at(Tuple::point(0.5, 2.0, 0.5)),
+        Tuple::vector(0.0, 1.0, 0.0)
+    );
+    assert_eq!(
+        c.normal_at(Tuple::point(0.0, -1.0, 0.5)),
+        Tuple::vector(0.0, -1.0, 0.0)
+    );
+}
+
+#[test]
+fn shade_cone_in_world() {
+    let mut world = World::new();
+    world.add_light(PointLight::new(Tuple::point(-10.0, 10.0, -10.0), WHITE));
+    let mut cone = Shape::truncated_cone(-1.0, 0.0, true);
+    cone.set_color(Color::new(0.8, 1.0, 0.6));
+    cone.translate(0.0, 0.5, 0.0);
+    let id = world.add_object(cone).unwrap();
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut xs = world.intersect(&r);
+    let hit = *xs.hit().unwrap();
+    assert_eq!(hit.object_id, id);
+    assert!(float_near_equal(hit.t, 4.5));
+
+    let comps = xs.prepare_computation(xs.hit_index, &r, &world);
+    let expected = world.shade_hit(&comps, MAX_REFLECT_DEPTH);
+    assert_ne!(expected, BLACK);
+    assert_eq!(world.color_at(&r, MAX_REFLECT_DEPTH), expected);
+}
@@ -0,0 +1,137 @@
+use ray_tracer::color::Color;
+use ray_tracer::float_near_equal;
+use ray_tracer::intersection::Intersection;
+use ray_tracer::point_light::PointLight;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::{CsgOperation, Shape, ShapeType};
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{BLACK, MAX_REFLECT_DEPTH, WHITE};
+
+#[test]
+fn csg_is_built_from_two_children() {
+    let c = Shape::csg(CsgOperation::Union, Shape::sphere(), Shape::cube());
+    assert_eq!(c.children().len(), 2);
+    assert_eq!(*c.children()[0].kind(), ShapeType::Sphere);
+    assert_eq!(*c.children()[1].kind(), ShapeType::Cube);
+}
+
+#[test]
+fn operation_rules() {
+    let cases = [
+        (CsgOperation::Union, true, true, true, false),
+        (CsgOperation::Union, true, true, false, true),
+        (CsgOperation::Union, true, false, true, false),
+        (CsgOperation::Union, true, false, false, true),
+        (CsgOperation::Union, false, true, true, false),
+        (CsgOperation::Union, false, true, false, false),
+        (CsgOperation::Union, false, false, true, true),
+        (CsgOperation::Union, false, false, false, true),
+        (CsgOperation::Intersection, true, true, true, true),
+        (CsgOperation::Intersection, true, true, false, false),
+        (CsgOperation::Intersection, true, false, true, true),
+        (CsgOperation::Intersection, true, false, false, false),
+        (CsgOperation::Intersection, false, true, true, true),
+        (CsgOperation::Intersection, false, true, false, true),
+        (CsgOperation::Intersection, false, false, true, false),
+        (CsgOperation::Intersection, false, false, false, false),
+        (CsgOperation::Difference, true, true, true, false),
+        (CsgOperation::Difference, true, true, false, true),
+        (CsgOperation::Difference, true, false, true, false),
+        (CsgOperation::Difference, true, false, false, true),
+        (CsgOperation::Difference, false, true, true, true),
+        (CsgOperation::Difference, false, true, false, true),
+        (CsgOperation::Difference, false, false, true, false),
+        (CsgOperation::Difference, false, false, false, false),
+    ];
+    for (op, left_hit, in_left, in_right, expected) in cases {
+        assert_eq!(op.allows(left_hit, in_left, in_right), expected);
+    }
+}
+
+#[test]
+fn filter_intersections() {
+    let cases = [
+        (CsgOperation::Union, 0, 3),
+        (CsgOperation::Intersection, 1, 2),
+        (CsgOperation::Difference, 0, 1),
+    ];
+    for (op, first, second) in cases {
+        let mut c = Shape::csg(op, Shape::sphere(), Shape::cube());
+        c.set_id(0);
+        let xs = vec![
+            Intersection::new(1.0, 1),
+            Intersection::new(2.0, 2),
+            Intersection::new(3.0, 1),
+            Intersection::new(4.0, 2),
+        ];
+        let result = c.filter_intersections(&xs);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], xs[first]);
+        assert_eq!(result[1], xs[second]);
+    }
+}
+
+#[test]
+fn ray_misses_csg() {
+    let mut c = Shape::csg(CsgOperation::Union, Shape::sphere(), Shape::cube());
+    c.set_id(0);
+    let r = Ray::new(Tuple::point(0.0, 2.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_eq!(c.intersect(&r).unwrap().len(), 0);
+}
+
+#[test]
+fn ray_hits_csg() {
+    let mut s2 = Shape::sphere();
+    s2.translate(0.0, 0.0, 0.5);
+    let mut c = Shape::csg(CsgOperation::Union, Shape::sphere(), s2);
+    c.set_id(0);
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = c.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 2);
+    assert!(float_near_equal(xs[0].t, 4.0));
+    assert_eq!(xs[0].object_id, 1);
+    assert!(float_near_equal(xs[1].t, 6.5));
+    assert_eq!(xs[1].object_id, 2);
+}
+
+#[test]
+fn transformed_csg_moves_children() {
+    let mut c = Shape::csg(CsgOperation::Difference, Shape::cube(), Shape::sphere());
+    c.translate(0.0, 0.0, 10.0);
+    c.set_id(0);
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = c.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 2);
+    assert!(float_near_equal(xs[0].t, 9.0));
+    assert_eq!(
+        c.children()[0].normal_at(Tuple::point(0.0, 0.0, 9.0)),
+        Tuple::vector(0.0, 0.0, -1.0)
+    );
+}
+
+#[test]
+fn shade_lens_in_world() {
+    let mut world = World::new();
+    world.add_light(PointLight::new(Tuple::point(-10.0, 10.0, -10.0), WHITE));
+    let mut left = Shape::sphere();
+    left.translate(0.0, 0.0, 0.5);
+    left.set_color(Color::new(0.2, 0.4, 0.9));
+    let mut right = Shape::sphere();
+    right.translate(0.0, 0.0, -0.5);
+    right.set_color(Color::new(0.9, 0.4, 0.2));
+    let lens = Shape::csg(CsgOperation::Intersection, left, right);
+    world.add_object(lens).unwrap();
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut xs = world.intersect(&r);
+    assert_eq!(xs.len(), 2);
+    let hit = *xs.hit().unwrap();
+    assert_eq!(hit.object_id, 1);
+    assert!(float_near_equal(hit.t, 4.5));
+
+    let comps = xs.prepare_computation(xs.hit_index, &r, &world);
+    let expected = world.shade_hit(&comps, MAX_REFLECT_DEPTH);
+    assert_ne!(expected, BLACK);
+    assert_eq!(world.color_at(&r, MAX_REFLECT_DEPTH), expected);
+}
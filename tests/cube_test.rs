@@ -0,0 +1,141 @@
+use ray_tracer::float_near_equal;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+
+#[test]
+fn ray_intersects_cube() {
+    let mut c = Shape::cube();
+    c.set_id(0);
+    let cases = [
+        (
+            Tuple::point(5.0, 0.5, 0.0),
+            Tuple::vector(-1.0, 0.0, 0.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(-5.0, 0.5, 0.0),
+            Tuple::vector(1.0, 0.0, 0.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(0.5, 5.0, 0.0),
+            Tuple::vector(0.0, -1.0, 0.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(0.5, -5.0, 0.0),
+            Tuple::vector(0.0, 1.0, 0.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(0.5, 0.0, 5.0),
+            Tuple::vector(0.0, 0.0, -1.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(0.5, 0.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(0.0, 0.5, 0.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            -1.0,
+            1.0,
+        ),
+    ];
+    for (origin, direction, t1, t2) in cases {
+        let xs = c.intersect(&Ray::new(origin, direction)).unwrap();
+        assert_eq!(xs.len(), 2);
+        assert!(float_near_equal(xs[0].t, t1));
+        assert!(float_near_equal(xs[1].t, t2));
+    }
+}
+
+#[test]
+fn ray_misses_cube() {
+    let mut c = Shape::cube();
+    c.set_id(0);
+    let cases = [
+        (
+            Tuple::point(-2.0, 0.0, 0.0),
+            Tuple::vector(0.2673, 0.5345, 0.8018),
+        ),
+        (
+            Tuple::point(0.0, -2.0, 0.0),
+            Tuple::vector(0.8018, 0.2673, 0.5345),
+        ),
+        (
+            Tuple::point(0.0, 0.0, -2.0),
+            Tuple::vector(0.5345, 0.8018, 0.2673),
+        ),
+        (Tuple::point(2.0, 0.0, 2.0), Tuple::vector(0.0, 0.0, -1.0)),
+        (Tuple::point(0.0, 2.0, 2.0), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(2.0, 2.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+    ];
+    for (origin, direction) in cases {
+        let xs = c.intersect(&Ray::new(origin, direction)).unwrap();
+        assert_eq!(xs.len(), 0);
+    }
+}
+
+#[test]
+fn normal_on_surface() {
+    let c = Shape::cube();
+    let cases = [
+        (Tuple::point(1.0, 0.5, -0.8), Tuple::vector(1.0, 0.0, 0.0)),
+        (Tuple::point(-1.0, -0.2, 0.9), Tuple::vector(-1.0, 0.0, 0.0)),
+        (Tuple::point(-0.4, 1.0, -0.1), Tuple::vector(0.0, 1.0, 0.0)),
+        (Tuple::point(0.3, -1.0, -0.7), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(-0.6, 0.3, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+        (Tuple::point(0.4, 0.4, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+        (Tuple::point(1.0, 1.0, 1.0), Tuple::vector(1.0, 0.0, 0.0)),
+        (
+            Tuple::point(-1.0, -1.0, -1.0),
+            Tuple::vector(-1.0, 0.0, 0.0),
+        ),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(c.normal_at(point), normal);
+    }
+}
+
+#[test]
+fn normal_on_transformed_cube() {
+    let mut c = Shape::cube();
+    c.scale(2.0, 1.0, 1.0);
+    c.translate(0.0, 3.0, 0.0);
+    assert_eq!(
+        c.normal_at(Tuple::point(2.0, 3.5, 0.0)),
+        Tuple::vector(1.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        c.normal_at(Tuple::point(0.5, 4.0, 0.0)),
+        Tuple::vector(0.0, 1.0, 0.0)
+    );
+}
+
+#[test]
+fn ray_starting_inside_cube() {
+    let mut world = World::new();
+    let id = world.add_object(Shape::cube()).unwrap();
+    let r = Ray::new(Tuple::point(0.0, 0.5, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut ix = world.intersect(&r);
+    assert_eq!(ix.len(), 2);
+    let hit = *ix.hit().unwrap();
+    assert_eq!(hit.object_id, id);
+    assert!(float_near_equal(hit.t, 1.0));
+
+    let comps = hit.prepare_computation(&r, &world);
+    assert!(comps.inside);
+    assert_eq!(comps.point, Tuple::point(0.0, 0.5, 1.0));
+    assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+}
@@ -0,0 +1,196 @@
+use ray_tracer::float_near_equal;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::{Shape, ShapeType};
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+
+#[test]
+fn default_extents() {
+    let c = Shape::cylinder();
+    assert_eq!(
+        *c.kind(),
+        ShapeType::Cylinder {
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+        }
+    );
+}
+
+#[test]
+fn ray_misses_cylinder() {
+    let mut c = Shape::cylinder();
+    c.set_id(0);
+    let cases = [
+        (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+        (Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+        (Tuple::point(0.0, 0.0, -5.0), Tuple::vector(1.0, 1.0, 1.0)),
+    ];
+    for (origin, direction) in cases {
+        let xs = c
+            .intersect(&Ray::new(origin, direction.normalize()))
+            .unwrap();
+        assert_eq!(xs.len(), 0);
+    }
+}
+
+#[test]
+fn ray_strikes_cylinder() {
+    let mut c = Shape::cylinder();
+    c.set_id(0);
+    let cases = [
+        (
+            Tuple::point(1.0, 0.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            5.0,
+            5.0,
+        ),
+        (
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            4.0,
+            6.0,
+        ),
+        (
+            Tuple::point(0.5, 0.0, -5.0),
+            Tuple::vector(0.1, 1.0, 1.0),
+            6.80798191702732,
+            7.088723439378861,
+        ),
+    ];
+    for (origin, direction, t0, t1) in cases {
+        let xs = c
+            .intersect(&Ray::new(origin, direction.normalize()))
+            .unwrap();
+        assert_eq!(xs.len(), 2);
+        assert!(float_near_equal(xs[0].t, t0));
+        assert!(float_near_equal(xs[1].t, t1));
+    }
+}
+
+#[test]
+fn normal_on_cylinder() {
+    let c = Shape::cylinder();
+    let cases = [
+        (Tuple::point(1.0, 0.0, 0.0), Tuple::vector(1.0, 0.0, 0.0)),
+        (Tuple::point(0.0, 5.0, -1.0), Tuple::vector(0.0, 0.0, -1.0)),
+        (Tuple::point(0.0, -2.0, 1.0), Tuple::vector(0.0, 0.0, 1.0)),
+        (Tuple::point(-1.0, 1.0, 0.0), Tuple::vector(-1.0, 0.0, 0.0)),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(c.normal_at(point), normal);
+    }
+}
+
+#[test]
+fn intersect_truncated_cylinder() {
+    let mut c = Shape::truncated_cylinder(1.0, 2.0, false);
+    c.set_id(0);
+    let cases = [
+        (Tuple::point(0.0, 1.5, 0.0), Tuple::vector(0.1, 1.0, 0.0), 0),
+        (
+            Tuple::point(0.0, 3.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            0,
+        ),
+        (
+            Tuple::point(0.0, 0.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            0,
+        ),
+        (
+            Tuple::point(0.0, 2.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            0,
+        ),
+        (
+            Tuple::point(0.0, 1.0, -5.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            0,
+        ),
+        (
+            Tuple::point(0.0, 1.5, -2.0),
+            Tuple::vector(0.0, 0.0, 1.0),
+            2,
+        ),
+    ];
+    for (origin, direction, count) in cases {
+        let xs = c
+            .intersect(&Ray::new(origin, direction.normalize()))
+            .unwrap();
+        assert_eq!(xs.len(), count);
+    }
+}
+
+#[test]
+fn intersect_capped_cylinder() {
+    let mut c = Shape::truncated_cylinder(1.0, 2.0, true);
+    c.set_id(0);
+    let cases = [
+        (Tuple::point(0.0, 3.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(0.0, 3.0, -2.0), Tuple::vector(0.0, -1.0, 2.0)),
+        (Tuple::point(0.0, 4.0, -2.0), Tuple::vector(0.0, -1.0, 1.0)),
+        (Tuple::point(0.0, 0.0, -2.0), Tuple::vector(0.0, 1.0, 2.0)),
+        (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 1.0)),
+    ];
+    for (origin, direction) in cases {
+        let xs = c
+            .intersect(&Ray::new(origin, direction.normalize()))
+            .unwrap();
+        assert_eq!(xs.len(), 2);
+    }
+}
+
+#[test]
+fn normal_on_caps() {
+    let c = Shape::truncated_cylinder(1.0, 2.0, true);
+    let cases = [
+        (Tuple::point(0.0, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(0.5, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(0.0, 1.0, 0.5), Tuple::vector(0.0, -1.0, 0.0)),
+        (Tuple::point(0.0, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+        (Tuple::point(0.5, 2.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
+        (Tuple::point(0.0, 2.0, 0.5), Tuple::vector(0.0, 1.0, 0.0)),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(c.normal_at(point), normal);
+    }
+}
+
+#[test]
+fn transformed_cylinder() {
+    let mut c = Shape::truncated_cylinder(-1.0, 1.0, true);
+    c.scale(2.0, 2.0, 2.0);
+    c.translate(0.0, 0.0, 5.0);
+    c.set_id(0);
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = c.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 2);
+    assert!(float_near_equal(xs[0].t, 3.0));
+    assert!(float_near_equal(xs[1].t, 7.0));
+    assert_eq!(
+        c.normal_at(Tuple::point(0.0, 2.0, 5.0)),
+        Tuple::vector(0.0, 1.0, 0.0)
+    );
+}
+
+#[test]
+fn refraction_through_cylinder() {
+    let mut world = World::new();
+    let mut c = Shape::truncated_cylinder(-1.0, 1.0, true);
+    c.set_transparency(1.0);
+    c.set_refractive_index(1.5);
+    world.add_object(c).unwrap();
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = world.intersect(&r);
+    assert_eq!(xs.len(), 2);
+
+    let entering = xs.prepare_computation(0, &r, &world);
+    assert!(float_near_equal(entering.n1, 1.0));
+    assert!(float_near_equal(entering.n2, 1.5));
+    let leaving = xs.prepare_computation(1, &r, &world);
+    assert!(float_near_equal(leaving.n1, 1.5));
+    assert!(float_near_equal(leaving.n2, 1.0));
+    assert!(leaving.inside);
+}
@@ -0,0 +1,104 @@
+use ray_tracer::color::Color;
+use ray_tracer::directional_light::DirectionalLight;
+use ray_tracer::float_near_equal;
+use ray_tracer::intersection::Intersection;
+use ray_tracer::point_light::PointLight;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{MAX_REFLECT_DEPTH, WHITE};
+
+#[test]
+fn directional_light_init() {
+    let light = DirectionalLight::new(Tuple::vector(0.0, -3.0, 0.0), WHITE);
+    assert_eq!(light.direction, Tuple::vector(0.0, -1.0, 0.0));
+    assert_eq!(light.lightv(), Tuple::vector(0.0, 1.0, 0.0));
+    assert_eq!(light.intensity, WHITE);
+}
+
+#[test]
+#[should_panic(expected = "direction")]
+fn directional_light_without_direction() {
+    let _ = DirectionalLight::new(Tuple::vector(0.0, 0.0, 0.0), WHITE);
+}
+
+#[test]
+fn lighting_is_the_same_everywhere() {
+    let object = Shape::sphere();
+    let m = object.material();
+    let light = DirectionalLight::new(Tuple::vector(0.0, 0.0, 1.0), WHITE);
+    let eyev = Tuple::vector(0.0, 0.0, -1.0);
+    let normalv = Tuple::vector(0.0, 0.0, -1.0);
+    for p in [
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::point(100.0, -50.0, 3.0),
+        Tuple::point(-1e6, 1e6, -1e6),
+    ] {
+        assert_eq!(
+            m.lighting(&light.into(), &object, p, eyev, normalv, 1.0),
+            Color::new(1.9, 1.9, 1.9)
+        );
+    }
+    // A point light straight ahead gives the same result at the origin.
+    let point = PointLight::new(Tuple::point(0.0, 0.0, -10.0), WHITE);
+    assert_eq!(
+        m.lighting(
+            &point.into(),
+            &object,
+            Tuple::point(0.0, 0.0, 0.0),
+            eyev,
+            normalv,
+            1.0
+        ),
+        Color::new(1.9, 1.9, 1.9)
+    );
+
+    let p = Tuple::point(0.0, 0.0, 0.0);
+    assert_eq!(
+        m.lighting(&light.into(), &object, p, eyev, normalv, 0.0),
+        Color::new(0.1, 0.1, 0.1)
+    );
+    let behind = DirectionalLight::new(Tuple::vector(0.0, 0.0, -1.0), WHITE);
+    assert_eq!(
+        m.lighting(&behind.into(), &object, p, eyev, normalv, 1.0),
+        Color::new(0.1, 0.1, 0.1)
+    );
+}
+
+#[test]
+fn shadows_from_directional_lights() {
+    let w = World::default();
+    let light = DirectionalLight::new(Tuple::vector(1.0, -1.0, 1.0), WHITE);
+    assert!(!w.is_shadowed(Tuple::point(0.0, 10.0, 0.0), &light.into()));
+    assert!(w.is_shadowed(Tuple::point(10.0, -10.0, 10.0), &light.into()));
+    assert!(!w.is_shadowed(Tuple::point(-2.0, 2.0, -2.0), &light.into()));
+    // Unlike a point light, there is nothing beyond the light to escape to.
+    assert!(w.is_shadowed(Tuple::point(1e4, -1e4, 1e4), &light.into()));
+}
+
+#[test]
+fn shadow_rays_extend_to_infinity() {
+    let mut w = World::new();
+    let mut floor = Shape::plane();
+    floor.set_specular(0.0);
+    w.add_object(floor).unwrap();
+    let mut cloud = Shape::sphere();
+    cloud.scale(1000.0, 1000.0, 1000.0);
+    cloud.translate(0.0, 1e6, 0.0);
+    w.add_object(cloud).unwrap();
+    let id = w.add_light(DirectionalLight::new(Tuple::vector(0.0, -1.0, 0.0), WHITE));
+    assert_eq!(id, 0);
+    let light = w.get_light(0).unwrap();
+    assert_eq!(light.id(), 0);
+    assert_eq!(light.position(), None);
+    assert_eq!(w.get_light(1), None);
+
+    let shade = |x: f64| {
+        let r = Ray::new(Tuple::point(x, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        let comps = Intersection::new(1.0, 0).prepare_computation(&r, &w);
+        w.shade_hit(&comps, MAX_REFLECT_DEPTH).red
+    };
+    assert!(float_near_equal(shade(0.0), 0.1));
+    assert!(float_near_equal(shade(5000.0), 1.0));
+}
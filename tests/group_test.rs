@@ -0,0 +1,182 @@
+use std::f64::consts::PI;
+
+use ray_tracer::float_near_equal;
+use ray_tracer::pattern::Pattern;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{BLACK, WHITE};
+
+// A sphere translated along x inside a scaled group inside a rotated group.
+fn nested_sphere(x: f64, y: f64, z: f64) -> Shape {
+    let mut s = Shape::sphere();
+    s.translate(5.0, 0.0, 0.0);
+    let mut g2 = Shape::group();
+    g2.scale(x, y, z);
+    g2.add_child(s);
+    let mut g1 = Shape::group();
+    g1.rotate_y(PI / 2.0);
+    g1.add_child(g2);
+    g1
+}
+
+#[test]
+fn new_group_is_empty() {
+    let mut g = Shape::group();
+    assert!(g.children().is_empty());
+    g.add_child(Shape::sphere());
+    assert_eq!(g.children().len(), 1);
+}
+
+#[test]
+fn intersect_empty_group() {
+    let mut g = Shape::group();
+    g.set_id(0);
+    let r = Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_eq!(g.intersect(&r).unwrap().len(), 0);
+}
+
+#[test]
+fn intersect_nonempty_group() {
+    let mut g = Shape::group();
+    let s1 = Shape::sphere();
+    let mut s2 = Shape::sphere();
+    s2.translate(0.0, 0.0, -3.0);
+    let mut s3 = Shape::sphere();
+    s3.translate(5.0, 0.0, 0.0);
+    g.add_child(s1);
+    g.add_child(s2);
+    g.add_child(s3);
+    g.set_id(0);
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut xs = g.intersect(&r).unwrap();
+    xs.sort_by(|a, b| a.t.partial_cmp(&b.t).unwrap());
+    let ids: Vec<usize> = xs.iter().map(|i| i.object_id).collect();
+    assert_eq!(ids, vec![2, 2, 1, 1]);
+}
+
+#[test]
+fn intersect_transformed_group() {
+    let mut g = Shape::group();
+    g.scale(2.0, 2.0, 2.0);
+    let mut s = Shape::sphere();
+    s.translate(5.0, 0.0, 0.0);
+    g.add_child(s);
+    g.set_id(0);
+
+    let r = Ray::new(Tuple::point(10.0, 0.0, -10.0), Tuple::vector(0.0, 0.0, 1.0));
+    assert_eq!(g.intersect(&r).unwrap().len(), 2);
+}
+
+#[test]
+fn world_to_object_through_groups() {
+    let g1 = nested_sphere(2.0, 2.0, 2.0);
+    let s = &g1.children()[0].children()[0];
+    assert_eq!(
+        s.world_to_object(Tuple::point(-2.0, 0.0, -10.0)),
+        Tuple::point(0.0, 0.0, -1.0)
+    );
+}
+
+#[test]
+fn normal_to_world_through_groups() {
+    let g1 = nested_sphere(1.0, 2.0, 3.0);
+    let s = &g1.children()[0].children()[0];
+    let third = 3_f64.sqrt() / 3.0;
+    assert_eq!(
+        s.normal_to_world(Tuple::vector(third, third, third)),
+        Tuple::vector(2.0 / 7.0, 3.0 / 7.0, -6.0 / 7.0)
+    );
+}
+
+#[test]
+fn normal_on_child_object() {
+    let g1 = nested_sphere(1.0, 2.0, 3.0);
+    let s = &g1.children()[0].children()[0];
+    let third = 3_f64.sqrt() / 3.0;
+    let point = Tuple::point(3.0 * third, 2.0 * third, -5.0 - third);
+    assert_eq!(
+        s.normal_at(point),
+        Tuple::vector(2.0 / 7.0, 3.0 / 7.0, -6.0 / 7.0)
+    );
+}
+
+#[test]
+#[should_panic(expected = "cannot be inverted")]
+fn normal_on_child_of_flattened_group() {
+    let mut g = Shape::group();
+    g.scale(0.0, 1.0, 1.0);
+    g.add_child(Shape::sphere());
+    let _ = g.children()[0].normal_at(Tuple::point(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn transforming_group_moves_children() {
+    let mut g = Shape::group();
+    g.add_child(Shape::sphere());
+    g.translate(0.0, 3.0, 0.0);
+    let s = &g.children()[0];
+    assert_eq!(
+        s.world_to_object(Tuple::point(0.0, 3.0, 0.0)),
+        Tuple::point(0.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        s.normal_at(Tuple::point(0.0, 4.0, 0.0)),
+        Tuple::vector(0.0, 1.0, 0.0)
+    );
+}
+
+#[test]
+fn world_assigns_ids_to_children() {
+    let mut world = World::new();
+    let mut g = Shape::group();
+    g.add_child(Shape::sphere());
+    let mut inner = Shape::group();
+    inner.add_child(Shape::cube());
+    g.add_child(inner);
+    let group_id = world.add_object(g).unwrap();
+    let plane_id = world.add_object(Shape::plane()).unwrap();
+
+    assert_eq!(group_id, 0);
+    assert_eq!(plane_id, 4);
+    assert_eq!(world.get_object(1).unwrap().id(), Some(1));
+    assert_eq!(world.get_object(3).unwrap().id(), Some(3));
+    assert_eq!(world.get_object(4).unwrap().id(), Some(4));
+    assert_eq!(world.get_object(5), None);
+}
+
+#[test]
+fn world_intersect_reports_child_ids() {
+    let mut world = World::new();
+    let mut g = Shape::group();
+    g.translate(0.0, 0.0, 2.0);
+    g.add_child(Shape::sphere());
+    world.add_object(g).unwrap();
+
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut xs = world.intersect(&r);
+    let hit = *xs.hit().unwrap();
+    assert_eq!(hit.object_id, 1);
+    assert!(float_near_equal(hit.t, 6.0));
+    let comps = hit.prepare_computation(&r, &world);
+    assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn pattern_on_nested_object() {
+    let mut g = Shape::group();
+    g.scale(2.0, 2.0, 2.0);
+    g.add_child(Shape::sphere());
+    let pattern = Pattern::stripes(WHITE, BLACK);
+    let s = &g.children()[0];
+    assert_eq!(
+        pattern.color_at_object(s, Tuple::point(1.5, 0.0, 0.0)),
+        WHITE
+    );
+    assert_eq!(
+        pattern.color_at_object(s, Tuple::point(2.5, 0.0, 0.0)),
+        BLACK
+    );
+}
@@ -0,0 +1,227 @@
+use ray_tracer::canvas::Canvas;
+use ray_tracer::color::Color;
+use ray_tracer::hdr_image::{HdrError, Pfm, RadianceHdr};
+
+fn hdr_canvas(width: usize, height: usize) -> Canvas {
+    let mut c = Canvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let values = [0.0, 0.5, 3.25, 100.0, 0.015625, 1.0, 7.5];
+            c.write_pixel(
+                x,
+                y,
+                Color::new(values[x % 7], values[(x + y) % 7], values[y % 7]),
+            );
+        }
+    }
+    c
+}
+
+#[test]
+fn pfm_header_and_layout() {
+    let mut c = Canvas::new(2, 2);
+    c.write_pixel(0, 1, Color::new(1.0, 2.0, -3.0));
+    let mut out = Vec::new();
+    Pfm::write(&c, &mut out).unwrap();
+    let header = b"PF\n2 2\n-1.0\n";
+    assert_eq!(&out[..header.len()], header);
+    assert_eq!(out.len(), header.len() + 2 * 2 * 12);
+    // The bottom row comes first.
+    let first = &out[header.len()..header.len() + 12];
+    assert_eq!(&first[0..4], 1.0f32.to_le_bytes());
+    assert_eq!(&first[4..8], 2.0f32.to_le_bytes());
+    assert_eq!(&first[8..12], (-3.0f32).to_le_bytes());
+}
+
+#[test]
+fn pfm_round_trip() {
+    let c = hdr_canvas(9, 4);
+    let mut out = Vec::new();
+    Pfm::write(&c, &mut out).unwrap();
+    let read = Pfm::parse(&out).unwrap();
+    assert_eq!(read.width(), 9);
+    assert_eq!(read.height(), 4);
+    assert_eq!(read.pixels(), c.pixels());
+}
+
+#[test]
+fn big_endian_and_greyscale_pfm() {
+    let mut data = b"Pf\n2 1\n1.0\n".to_vec();
+    data.extend_from_slice(&0.25f32.to_be_bytes());
+    data.extend_from_slice(&42.0f32.to_be_bytes());
+    let c = Pfm::parse(&data).unwrap();
+    assert_eq!(c.pixel_at(0, 0), Color::new(0.25, 0.25, 0.25));
+    assert_eq!(c.pixel_at(1, 0), Color::new(42.0, 42.0, 42.0));
+}
+
+#[test]
+fn malformed_pfm() {
+    assert!(matches!(
+        Pfm::parse(b"P6\n1 1\n255\n"),
+        Err(HdrError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        Pfm::parse(b"PF\n1 x\n-1.0\n"),
+        Err(HdrError::Malformed { .. })
+    ));
+    assert!(matches!(
+        Pfm::parse(b"PF\n1 1\n0\n"),
+        Err(HdrError::Malformed { .. })
+    ));
+    let mut data = b"PF\n2 1\n-1.0\n".to_vec();
+    data.extend_from_slice(&[0; 20]);
+    assert!(matches!(
+        Pfm::parse(&data),
+        Err(HdrError::Truncated {
+            expected: 2,
+            found: 1
+        })
+    ));
+}
+
+#[test]
+fn rgbe_header() {
+    let mut out = Vec::new();
+    RadianceHdr::write(&Canvas::new(3, 2), &mut out).unwrap();
+    let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 3\n";
+    assert_eq!(&out[..header.len()], header);
+    // Narrow scanlines are stored flat, and black is all zeroes.
+    assert_eq!(&out[header.len()..], [0; 24]);
+}
+
+#[test]
+fn rgbe_round_trip() {
+    for (width, height) in [(5, 3), (40, 6), (300, 2)] {
+        let c = hdr_canvas(width, height);
+        let mut out = Vec::new();
+        RadianceHdr::write(&c, &mut out).unwrap();
+        let read = RadianceHdr::parse(&out).unwrap();
+        assert_eq!(read.width(), width);
+        assert_eq!(read.height(), height);
+        for (expected, actual) in c.pixels().iter().zip(read.pixels()) {
+            let max = expected.red.max(expected.green).max(expected.blue);
+            for (e, a) in [
+                (expected.red, actual.red),
+                (expected.green, actual.green),
+                (expected.blue, actual.blue),
+            ] {
+                // Each channel keeps eight bits relative to the brightest one.
+                assert!((e - a).abs() <= max / 128.0, "{e} read back as {a}");
+            }
+        }
+    }
+}
+
+#[test]
+fn empty_rgbe_round_trip() {
+    for (width, height) in [(0, 3), (4, 0)] {
+        let mut out = Vec::new();
+        RadianceHdr::write(&Canvas::new(width, height), &mut out).unwrap();
+        let read = RadianceHdr::parse(&out).unwrap();
+        assert_eq!((read.width(), read.height()), (width, height));
+    }
+}
+
+#[test]
+fn rgbe_compresses_runs() {
+    let mut c = Canvas::new(200, 1);
+    for x in 0..200 {
+        c.write_pixel(x, 0, Color::new(2.0, 1.0, 0.5));
+    }
+    let mut out = Vec::new();
+    RadianceHdr::write(&c, &mut out).unwrap();
+    assert!(out.len() < 100);
+    let read = RadianceHdr::parse(&out).unwrap();
+    assert_eq!(read.pixel_at(199, 0), read.pixel_at(0, 0));
+}
+
+#[test]
+fn reading_encoded_scanlines() {
+    let mut data =
+        b"#?RGBE\n# made by hand\nFORMAT=32-bit_rle_rgbe\nEXPOSURE=1.0\n\n-Y 2 +X 8\n".to_vec();
+    // A run length encoded scanline: red is a run of eight 128s, green is eight literals,
+    // blue is two runs and the exponent is one run.
+    data.extend_from_slice(&[2, 2, 0, 8]);
+    data.extend_from_slice(&[136, 128]);
+    data.extend_from_slice(&[8, 0, 16, 32, 48, 64, 80, 96, 112]);
+    data.extend_from_slice(&[132, 0, 132, 255]);
+    data.extend_from_slice(&[136, 129]);
+    // A flat scanline of eight identical pixels.
+    for _ in 0..8 {
+        data.extend_from_slice(&[64, 64, 64, 130]);
+    }
+    let c = RadianceHdr::parse(&data).unwrap();
+    assert_eq!(
+        c.pixel_at(0, 0),
+        Color::new(1.00390625, 0.00390625, 0.00390625)
+    );
+    assert_eq!(
+        c.pixel_at(7, 0),
+        Color::new(1.00390625, 0.87890625, 1.99609375)
+    );
+    assert_eq!(
+        c.pixel_at(3, 1),
+        Color::new(1.0078125, 1.0078125, 1.0078125)
+    );
+}
+
+#[test]
+fn malformed_rgbe() {
+    assert!(matches!(
+        RadianceHdr::parse(b"PF\n1 1\n-1.0\n"),
+        Err(HdrError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        RadianceHdr::parse(b"#?RADIANCE\nFORMAT=32-bit_rle_xyze\n\n-Y 1 +X 1\n\0\0\0\0"),
+        Err(HdrError::UnsupportedFormat(_))
+    ));
+    assert!(matches!(
+        RadianceHdr::parse(b"#?RADIANCE\n\n+X 1 -Y 1\n\0\0\0\0"),
+        Err(HdrError::Malformed { .. })
+    ));
+    assert!(matches!(
+        RadianceHdr::parse(b"#?RADIANCE\n\n-Y 2 +X 1\n\0\0\0\0"),
+        Err(HdrError::Truncated {
+            expected: 2,
+            found: 1
+        })
+    ));
+    assert!(matches!(
+        RadianceHdr::parse(b"#?RADIANCE\n\n-Y 4000000000 +X 4000000000\n\0\0\0\0"),
+        Err(HdrError::Truncated { found: 0, .. })
+    ));
+    let empty = RadianceHdr::parse(b"#?RADIANCE\n\n-Y 4000000000 +X 0\n").unwrap();
+    assert_eq!((empty.width(), empty.height()), (0, 4_000_000_000));
+    assert!(matches!(
+        RadianceHdr::parse(b"#?RADIANCE\n\n-Y 2 +X 18446744073709551615\n\0\0\0\0"),
+        Err(HdrError::Malformed { .. })
+    ));
+    let mut data = b"#?RADIANCE\n\n-Y 1 +X 8\n".to_vec();
+    data.extend_from_slice(&[2, 2, 0, 8, 137, 1, 136, 1, 136, 1, 136, 1]);
+    assert!(matches!(
+        RadianceHdr::parse(&data),
+        Err(HdrError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn saving_hdr_formats_by_extension() {
+    let dir = std::env::temp_dir();
+    let c = hdr_canvas(10, 3);
+
+    // The files are removed as soon as they have been read, so a failed assertion does not
+    // leave them behind.
+    let pfm = dir.join("ray_tracer_save_test.pfm");
+    c.save(pfm.to_str().unwrap()).unwrap();
+    let read = Pfm::from_file(pfm.to_str().unwrap());
+    std::fs::remove_file(pfm).unwrap();
+    assert_eq!(read.unwrap().pixels(), c.pixels());
+
+    let hdr = dir.join("ray_tracer_save_test.hdr");
+    c.save(hdr.to_str().unwrap()).unwrap();
+    let read = RadianceHdr::from_file(hdr.to_str().unwrap());
+    std::fs::remove_file(hdr).unwrap();
+    let read = read.unwrap();
+    assert_eq!(read.width(), 10);
+    assert!((read.pixel_at(3, 0).red - 100.0).abs() < 1.0);
+}
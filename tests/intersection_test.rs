@@ -124,7 +124,7 @@ fn prepare_comps() {
     let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
     let s = Shape::sphere();
     let mut world = World::new();
-    let object_id = world.add_object(s);
+    let object_id = world.add_object(s).unwrap();
     let i = Intersection::new(4.0, object_id);
     let comps = i.prepare_computation(&r, &world);
     assert_eq!(comps.object_id, object_id);
@@ -150,7 +150,7 @@ fn precompute_reflection() {
         Tuple::point(0.0, 1.0, -1.0),
         Tuple::vector(0.0, -(2_f64.sqrt()) / 2.0, (2_f64.sqrt()) / 2.0),
     );
-    world.add_object(shape);
+    world.add_object(shape).unwrap();
     let i = Intersection::new(2_f64.sqrt(), 0);
     let comps = i.prepare_computation(&r, &world);
     assert_eq!(
@@ -172,9 +172,9 @@ fn precompute_n1_and_n2() {
     c.translate(0.0, 0.0, 0.25);
     c.set_refractive_index(2.5);
 
-    world.add_object(a);
-    world.add_object(b);
-    world.add_object(c);
+    world.add_object(a).unwrap();
+    world.add_object(b).unwrap();
+    world.add_object(c).unwrap();
 
     let r = Ray::new(Tuple::point(0.0, 0.0, -4.0), Tuple::vector(0.0, 0.0, 1.0));
     let intersections = vec![
@@ -209,7 +209,7 @@ fn hit_offsets_point() {
     let mut shape = Shape::sphere();
     shape.translate(0.0, 0.0, 1.0);
     let mut world = World::new();
-    let id = world.add_object(shape);
+    let id = world.add_object(shape).unwrap();
     let i = Intersection::new(5.0, id);
     let comps = i.prepare_computation(&r, &world);
     assert!(comps.over_point.z < EPSILON / 2.0);
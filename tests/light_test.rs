@@ -1,9 +1,16 @@
+use ray_tracer::area_light::AreaLight;
 use ray_tracer::color::Color;
+use ray_tracer::directional_light::DirectionalLight;
 use ray_tracer::float_near_equal;
+use ray_tracer::light::{Light, LightSample};
 use ray_tracer::material::Material;
-use ray_tracer::point_light::PointLight;
+use ray_tracer::point_light::{Attenuation, PointLight};
+use ray_tracer::ray::Ray;
 use ray_tracer::shape::Shape;
+use ray_tracer::spot_light::SpotLight;
 use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{MAX_REFLECT_DEPTH, WHITE};
 
 #[test]
 fn light_init() {
@@ -33,23 +40,23 @@ fn lighting() {
     let mut eye = Tuple::vector(0.0, 0.0, -1.0);
     let normal = Tuple::vector(0.0, 0.0, -1.0);
     let mut light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
-    let mut result = m.lighting(&light, &object, position, eye, normal, false);
+    let mut result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
     assert_eq!(result, Color::new(1.9, 1.9, 1.9));
 
     eye = Tuple::vector(0.0, 2_f64.sqrt() / 2.0, -(2_f64.sqrt()) / 2.0);
-    result = m.lighting(&light, &object, position, eye, normal, false);
+    result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
     assert_eq!(result, Color::new(1.0, 1.0, 1.0));
 
     eye = Tuple::vector(0.0, 0.0, -1.0);
     light = PointLight::new(Tuple::point(0.0, 10.0, -10.0), Color::new(1.0, 1.0, 1.0));
-    result = m.lighting(&light, &object, position, eye, normal, false);
+    result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
     assert_eq!(
         result,
         Color::new(0.7363961030678927, 0.7363961030678927, 0.7363961030678927)
     );
 
     eye = Tuple::vector(0.0, -(2_f64.sqrt()) / 2.0, -(2_f64.sqrt()) / 2.0);
-    result = m.lighting(&light, &object, position, eye, normal, false);
+    result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
     assert_eq!(
         result,
         Color::new(1.6363961030678928, 1.6363961030678928, 1.6363961030678928)
@@ -57,7 +64,7 @@ fn lighting() {
 
     eye = Tuple::vector(0.0, 0.0, -1.0);
     light = PointLight::new(Tuple::point(0.0, 0.0, 10.0), Color::new(1.0, 1.0, 1.0));
-    result = m.lighting(&light, &object, position, eye, normal, false);
+    result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
     assert_eq!(result, Color::new(0.1, 0.1, 0.1));
 }
 
@@ -69,6 +76,253 @@ fn lighting_in_shadow() {
     let eyev = Tuple::vector(0.0, 0.0, -1.0);
     let normalv = Tuple::vector(0.0, 0.0, -1.0);
     let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), Color::new(1.0, 1.0, 1.0));
-    let result = m.lighting(&light, &object, position, eyev, normalv, true);
+    let result = m.lighting(&light.into(), &object, position, eyev, normalv, 0.0);
     assert_eq!(result, Color::new(0.1, 0.1, 0.1));
 }
+
+#[test]
+fn light_abstraction() {
+    let point = Light::from(PointLight::new(
+        Tuple::point(0.0, 3.0, 4.0),
+        Color::new(0.5, 0.5, 0.5),
+    ));
+    let from = Tuple::point(0.0, 0.0, 0.0);
+    assert_eq!(point.position(), Some(Tuple::point(0.0, 3.0, 4.0)));
+    assert_eq!(point.direction_from(from), Tuple::vector(0.0, 0.6, 0.8));
+    assert!(float_near_equal(point.distance_from(from), 5.0));
+    assert_eq!(point.intensity_at(from), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(
+        point.samples(from),
+        vec![LightSample {
+            direction: Tuple::vector(0.0, 0.6, 0.8),
+            distance: 5.0
+        }]
+    );
+
+    let sun = Light::from(DirectionalLight::new(Tuple::vector(0.0, -1.0, 0.0), WHITE));
+    assert_eq!(sun.position(), None);
+    assert_eq!(sun.direction_from(from), Tuple::vector(0.0, 1.0, 0.0));
+    assert!(sun.distance_from(from).is_infinite());
+    assert_eq!(sun.samples(from).len(), 1);
+
+    let spot = Light::from(SpotLight::new(
+        Tuple::point(0.0, 10.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        0.1,
+        0.2,
+        WHITE,
+    ));
+    assert_eq!(spot.intensity(), WHITE);
+    assert_eq!(spot.intensity_at(from), WHITE);
+    assert_eq!(
+        spot.intensity_at(Tuple::point(10.0, 0.0, 0.0)),
+        Color::new(0.0, 0.0, 0.0)
+    );
+
+    let area = Light::from(AreaLight::new(
+        Tuple::point(-1.0, 5.0, -1.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        2,
+        Tuple::vector(0.0, 0.0, 2.0),
+        3,
+        WHITE,
+    ));
+    assert_eq!(area.position(), Some(Tuple::point(0.0, 5.0, 0.0)));
+    assert!(float_near_equal(area.distance_from(from), 5.0));
+    let samples = area.samples(from);
+    assert_eq!(samples.len(), 6);
+    assert!(samples
+        .iter()
+        .all(|s| float_near_equal(s.direction.magnitude(), 1.0) && s.distance > 5.0));
+}
+
+#[test]
+fn worlds_hold_every_kind_of_light() {
+    let mut w = World::default();
+    assert_eq!(
+        w.add_light(DirectionalLight::new(Tuple::vector(0.0, -1.0, 0.0), WHITE)),
+        1
+    );
+    let area = AreaLight::new(
+        Tuple::point(-1.0, 5.0, -1.0),
+        Tuple::vector(2.0, 0.0, 0.0),
+        2,
+        Tuple::vector(0.0, 0.0, 2.0),
+        2,
+        WHITE,
+    );
+    assert_eq!(w.add_light(area), 2);
+    assert_eq!(w.get_light(2).unwrap().id(), 2);
+    assert!(matches!(w.get_light(1), Some(Light::Directional(_))));
+
+    let spot = SpotLight::new(
+        Tuple::point(0.0, 10.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        0.1,
+        0.2,
+        WHITE,
+    );
+    w.set_light(1, spot).unwrap();
+    assert!(matches!(w.get_light(1), Some(Light::Spot(l)) if l.id == 1));
+    assert!(w.set_light(3, spot).is_err());
+
+    // A point beside and below the sphere sees only part of the area light above it, so it
+    // is partly lit rather than in shadow.
+    let p = Tuple::point(1.3, -1.0, 0.0);
+    let area = w.get_light(2).unwrap();
+    let visibility = w.light_visibility(p, area);
+    assert!(visibility > 0.0 && visibility < 1.0);
+    assert!(!w.is_shadowed(p, area));
+    assert!(w.is_shadowed(Tuple::point(0.0, -1.5, 0.0), area));
+}
+
+#[test]
+fn attenuation_models() {
+    assert!(float_near_equal(Attenuation::None.factor(100.0), 1.0));
+    assert!(float_near_equal(
+        Attenuation::InverseSquare.factor(2.0),
+        0.25
+    ));
+    assert!(float_near_equal(
+        Attenuation::InverseSquare.factor(0.5),
+        4.0
+    ));
+    let polynomial = Attenuation::Polynomial {
+        constant: 1.0,
+        linear: 0.5,
+        quadratic: 0.25,
+    };
+    assert!(float_near_equal(polynomial.factor(0.0), 1.0));
+    assert!(float_near_equal(polynomial.factor(2.0), 1.0 / 3.0));
+}
+
+#[test]
+fn attenuation_stays_finite() {
+    assert!(Attenuation::InverseSquare.factor(0.0).is_finite());
+    let linear = Attenuation::Polynomial {
+        constant: 0.0,
+        linear: 1.0,
+        quadratic: 0.0,
+    };
+    assert!(linear.factor(0.0).is_finite());
+    assert!(float_near_equal(linear.factor(2.0), 0.5));
+}
+
+#[test]
+#[should_panic(expected = "attenuation coefficients")]
+fn attenuation_without_constant_or_linear_term() {
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), WHITE);
+    light.set_attenuation(Attenuation::Polynomial {
+        constant: 0.0,
+        linear: 0.0,
+        quadratic: 1.0,
+    });
+}
+
+#[test]
+#[should_panic(expected = "attenuation coefficients")]
+fn negative_attenuation_coefficient() {
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), WHITE);
+    light.set_attenuation(Attenuation::Polynomial {
+        constant: 1.0,
+        linear: -0.5,
+        quadratic: 0.0,
+    });
+}
+
+#[test]
+#[should_panic(expected = "attenuation coefficients")]
+fn nan_attenuation_coefficient() {
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), WHITE);
+    light.set_attenuation(Attenuation::Polynomial {
+        constant: 1.0,
+        linear: 0.0,
+        quadratic: f64::NAN,
+    });
+}
+
+#[test]
+#[should_panic(expected = "cutoff radius")]
+fn negative_cutoff() {
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), WHITE);
+    light.set_cutoff(-1.0);
+}
+
+#[test]
+#[should_panic(expected = "cutoff radius")]
+fn nan_cutoff() {
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, 0.0), WHITE);
+    light.set_cutoff(f64::NAN);
+}
+
+#[test]
+fn attenuated_point_lights() {
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), WHITE);
+    assert_eq!(light.attenuation(), Attenuation::None);
+    assert!(light.cutoff().is_infinite());
+    let p = Tuple::point(0.0, 0.0, 0.0);
+    assert_eq!(light.intensity_at(p), WHITE);
+
+    light.set_attenuation(Attenuation::InverseSquare);
+    assert_eq!(light.intensity_at(p), Color::new(0.01, 0.01, 0.01));
+    assert_eq!(
+        Light::from(light).intensity_at(Tuple::point(0.0, 0.0, -8.0)),
+        Color::new(0.25, 0.25, 0.25)
+    );
+
+    light.set_cutoff(9.0);
+    assert_eq!(light.intensity_at(p), Color::new(0.0, 0.0, 0.0));
+    assert_eq!(
+        light.intensity_at(Tuple::point(0.0, 0.0, -8.0)),
+        Color::new(0.25, 0.25, 0.25)
+    );
+}
+
+#[test]
+fn lighting_with_attenuation() {
+    let m = Material::new();
+    let object = Shape::sphere();
+    let position = Tuple::point(0.0, 0.0, 0.0);
+    let eye = Tuple::vector(0.0, 0.0, -1.0);
+    let normal = Tuple::vector(0.0, 0.0, -1.0);
+    let mut light = PointLight::new(Tuple::point(0.0, 0.0, -2.0), WHITE);
+
+    // Diffuse and specular light are quartered at twice the distance, ambient light is not.
+    light.set_attenuation(Attenuation::InverseSquare);
+    let result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
+    assert_eq!(
+        result,
+        Color::new(0.1 + 1.8 / 4.0, 0.1 + 1.8 / 4.0, 0.1 + 1.8 / 4.0)
+    );
+
+    light.set_attenuation(Attenuation::Polynomial {
+        constant: 1.0,
+        linear: 0.0,
+        quadratic: 0.0,
+    });
+    let result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
+    assert_eq!(result, Color::new(1.9, 1.9, 1.9));
+
+    light.set_cutoff(1.5);
+    let result = m.lighting(&light.into(), &object, position, eye, normal, 1.0);
+    assert_eq!(result, Color::new(0.1, 0.1, 0.1));
+}
+
+#[test]
+fn nearby_lights_look_brighter() {
+    let mut w = World::new();
+    let mut floor = Shape::plane();
+    floor.set_specular(0.0);
+    w.add_object(floor).unwrap();
+    let mut light = PointLight::new(Tuple::point(0.0, 2.0, 0.0), WHITE);
+    light.set_attenuation(Attenuation::InverseSquare);
+    w.add_light(light);
+
+    let color_at = |x: f64| {
+        let r = Ray::new(Tuple::point(x, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        w.color_at(&r, MAX_REFLECT_DEPTH).red
+    };
+    let (near, far) = (color_at(0.0), color_at(6.0));
+    assert!(near > far);
+    assert!(float_near_equal(near, 0.1 + 0.9 / 4.0));
+}
@@ -412,3 +412,93 @@ fn multiply_by_inverse() {
     let c = a.matrix_multiply(&b);
     assert_eq!(c.matrix_multiply(&b.inverse()), expected);
 }
+
+fn as_matrix(m: &Matrix4) -> Matrix {
+    Matrix::new(
+        (0..4)
+            .map(|row| (0..4).map(|col| m.get(row, col)).collect())
+            .collect(),
+    )
+}
+
+fn sample_matrices() -> Vec<Matrix4> {
+    vec![
+        Matrix4::new([
+            [-5.0, 2.0, 6.0, -8.0],
+            [1.0, -5.0, 1.0, 8.0],
+            [7.0, 7.0, -6.0, -7.0],
+            [1.0, -3.0, 7.0, 4.0],
+        ]),
+        Matrix4::new([
+            [8.0, -5.0, 9.0, 2.0],
+            [7.0, 5.0, 6.0, 1.0],
+            [-6.0, 0.0, 9.0, 6.0],
+            [-3.0, 0.0, -9.0, -4.0],
+        ]),
+        Matrix4::new([
+            [9.0, 3.0, 0.0, 9.0],
+            [-5.0, -2.0, -6.0, -3.0],
+            [-4.0, 9.0, 6.0, 4.0],
+            [-7.0, 6.0, 6.0, 2.0],
+        ]),
+    ]
+}
+
+#[test]
+fn matrix4_matches_general_matrix() {
+    let samples = sample_matrices();
+    for a in &samples {
+        let general = as_matrix(a);
+        assert!(float_near_equal(a.determinant(), general.determinant()));
+        assert_eq!(as_matrix(&a.transpose()), general.transpose());
+        assert_eq!(as_matrix(&a.inverse()), general.inverse());
+        for b in &samples {
+            assert_eq!(
+                as_matrix(&a.matrix_multiply(b)),
+                general.matrix_multiply(&as_matrix(b))
+            );
+        }
+        let t = Tuple::new(1.0, 2.0, 3.0, 1.0);
+        assert_eq!(a.tuple_multiply(&t), general.tuple_multiply(&t));
+    }
+}
+
+#[test]
+fn matrix4_inverse() {
+    let a = sample_matrices()[0];
+    assert!(float_near_equal(a.determinant(), 532.0));
+    assert!(float_near_equal(a.inverse().get(2, 3), 105.0 / 532.0));
+    assert_eq!(a.matrix_multiply(&a.inverse()), Matrix4::identity_matrix());
+
+    let singular = Matrix4::new([
+        [-4.0, 2.0, -2.0, -3.0],
+        [9.0, 6.0, 2.0, 6.0],
+        [0.0, -5.0, 1.0, -5.0],
+        [0.0, 0.0, 0.0, 0.0],
+    ]);
+    assert!(!singular.is_invertible());
+}
+
+#[test]
+fn matrix4_get_and_set() {
+    let mut m = Matrix4::default();
+    assert_eq!(m, Matrix4::identity_matrix());
+    m.set(1, 3, 7.5);
+    assert!(float_near_equal(m.get(1, 3), 7.5));
+    assert!(float_near_equal(m.transpose().get(3, 1), 7.5));
+    assert_ne!(m, Matrix4::identity_matrix());
+}
+
+#[test]
+fn inverting_singular_matrices() {
+    let rows = [
+        [-4.0, 2.0, -2.0, -3.0],
+        [9.0, 6.0, 2.0, 6.0],
+        [0.0, -5.0, 1.0, -5.0],
+        [0.0, 0.0, 0.0, 0.0],
+    ];
+    assert_eq!(Matrix4::new(rows).try_inverse(), Err(SingularMatrix));
+    let general = Matrix::new(rows.iter().map(|row| row.to_vec()).collect());
+    assert_eq!(general.try_inverse(), Err(SingularMatrix));
+    assert!(Matrix4::identity_matrix().try_inverse().is_ok());
+}
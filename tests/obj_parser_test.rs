@@ -0,0 +1,219 @@
+use ray_tracer::float_near_equal;
+use ray_tracer::obj_parser::{IgnoredLine, ObjError, ObjParser};
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::{Shape, ShapeType};
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+
+fn triangle_points(shape: &Shape) -> (Tuple, Tuple, Tuple) {
+    match *shape.kind() {
+        ShapeType::Triangle { p1, p2, p3, .. } | ShapeType::SmoothTriangle { p1, p2, p3, .. } => {
+            (p1, p2, p3)
+        }
+        _ => panic!("expected a triangle"),
+    }
+}
+
+#[test]
+fn ignores_unrecognized_lines() {
+    let gibberish = "There was a young lady named Bright\n\
+                     who traveled much faster than light.\n\
+                     # a comment\n\
+                     \n\
+                     mtllib scene.mtl";
+    let parser = ObjParser::parse(gibberish).unwrap();
+    assert_eq!(
+        parser.ignored_lines(),
+        &[
+            IgnoredLine {
+                line: 1,
+                text: "There was a young lady named Bright".to_string(),
+            },
+            IgnoredLine {
+                line: 2,
+                text: "who traveled much faster than light.".to_string(),
+            },
+            IgnoredLine {
+                line: 5,
+                text: "mtllib scene.mtl".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn vertex_records() {
+    let file = "v -1 1 0\nv -1.0000 0.5000 0.0000\nv 1 0 0\nv 1 1 0";
+    let parser = ObjParser::parse(file).unwrap();
+    assert_eq!(parser.vertex(1), Some(Tuple::point(-1.0, 1.0, 0.0)));
+    assert_eq!(parser.vertex(2), Some(Tuple::point(-1.0, 0.5, 0.0)));
+    assert_eq!(parser.vertex(3), Some(Tuple::point(1.0, 0.0, 0.0)));
+    assert_eq!(parser.vertex(4), Some(Tuple::point(1.0, 1.0, 0.0)));
+    assert_eq!(parser.vertex(0), None);
+    assert_eq!(parser.vertex(5), None);
+}
+
+#[test]
+fn triangle_faces() {
+    let file = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\nf 1 2 3\nf 1 3 4";
+    let parser = ObjParser::parse(file).unwrap();
+    let children = parser.default_group().children();
+    assert_eq!(children.len(), 2);
+    assert_eq!(
+        triangle_points(&children[0]),
+        (
+            Tuple::point(-1.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0)
+        )
+    );
+    assert_eq!(
+        triangle_points(&children[1]),
+        (
+            Tuple::point(-1.0, 1.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0),
+            Tuple::point(1.0, 1.0, 0.0)
+        )
+    );
+}
+
+#[test]
+fn triangulating_polygons() {
+    let file = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\nv 0 2 0\n\nf 1 2 3 4 5";
+    let parser = ObjParser::parse(file).unwrap();
+    let children = parser.default_group().children();
+    assert_eq!(children.len(), 3);
+    let p = |i| parser.vertex(i).unwrap();
+    assert_eq!(triangle_points(&children[0]), (p(1), p(2), p(3)));
+    assert_eq!(triangle_points(&children[1]), (p(1), p(3), p(4)));
+    assert_eq!(triangle_points(&children[2]), (p(1), p(4), p(5)));
+}
+
+#[test]
+fn named_groups() {
+    let file = "v -1 1 0\nv -1 0 0\nv 1 0 0\nv 1 1 0\n\
+                g FirstGroup\nf 1 2 3\n\
+                o SecondGroup\nf 1 3 4\n\
+                g FirstGroup\nf 2 3 4";
+    let parser = ObjParser::parse(file).unwrap();
+    assert!(parser.default_group().children().is_empty());
+    assert_eq!(parser.group("FirstGroup").unwrap().children().len(), 2);
+    assert_eq!(parser.group("SecondGroup").unwrap().children().len(), 1);
+    assert!(parser.group("ThirdGroup").is_none());
+
+    let g = parser.into_group();
+    assert_eq!(g.children().len(), 2);
+}
+
+#[test]
+fn vertex_normals_and_texture_coords() {
+    let file = "vn 0 0 1\nvn 0.707 0 -0.707\nvn 1 2 3\nvt 0.5 0.25";
+    let parser = ObjParser::parse(file).unwrap();
+    assert_eq!(parser.normal(1), Some(Tuple::vector(0.0, 0.0, 1.0)));
+    assert_eq!(parser.normal(2), Some(Tuple::vector(0.707, 0.0, -0.707)));
+    assert_eq!(parser.normal(3), Some(Tuple::vector(1.0, 2.0, 3.0)));
+    assert_eq!(parser.texture_coord(1), Some((0.5, 0.25)));
+}
+
+#[test]
+fn texture_coords_default_missing_values() {
+    let parser = ObjParser::parse("vt 0.5\nvt 0.5 0.25 1").unwrap();
+    assert_eq!(parser.texture_coord(1), Some((0.5, 0.0)));
+    assert_eq!(parser.texture_coord(2), Some((0.5, 0.25)));
+}
+
+#[test]
+fn faces_with_normals() {
+    let file = "v 0 1 0\nv -1 0 0\nv 1 0 0\n\
+                vt 0 0\n\
+                vn -1 0 0\nvn 1 0 0\nvn 0 1 0\n\
+                f 1//3 2//1 3//2\n\
+                f 1/1/3 2/1/1 3/1/2\n\
+                f -3 -2 -1";
+    let parser = ObjParser::parse(file).unwrap();
+    let children = parser.default_group().children();
+    assert_eq!(children.len(), 3);
+    let expected = ShapeType::SmoothTriangle {
+        p1: Tuple::point(0.0, 1.0, 0.0),
+        p2: Tuple::point(-1.0, 0.0, 0.0),
+        p3: Tuple::point(1.0, 0.0, 0.0),
+        n1: Tuple::vector(0.0, 1.0, 0.0),
+        n2: Tuple::vector(-1.0, 0.0, 0.0),
+        n3: Tuple::vector(1.0, 0.0, 0.0),
+        e1: Tuple::vector(-1.0, -1.0, 0.0),
+        e2: Tuple::vector(1.0, -1.0, 0.0),
+    };
+    assert_eq!(*children[0].kind(), expected);
+    assert_eq!(*children[1].kind(), expected);
+    assert!(matches!(children[2].kind(), ShapeType::Triangle { .. }));
+    assert_eq!(
+        triangle_points(&children[2]),
+        (
+            Tuple::point(0.0, 1.0, 0.0),
+            Tuple::point(-1.0, 0.0, 0.0),
+            Tuple::point(1.0, 0.0, 0.0)
+        )
+    );
+}
+
+#[test]
+fn malformed_indices_are_errors() {
+    let vertices = "v 0 1 0\nv -1 0 0\nv 1 0 0\n";
+    for face in [
+        "f 1 2 4",
+        "f 0 1 2",
+        "f 1 2 x",
+        "f 1/1 2 3",
+        "f 1//2 2 3",
+        "f 1/2/3/4 2 3",
+    ] {
+        let file = format!("{vertices}{face}");
+        match ObjParser::parse(&file) {
+            Err(ObjError::InvalidIndex { line, .. }) => assert_eq!(line, 4),
+            _ => panic!("expected an invalid index error for {face}"),
+        }
+    }
+}
+
+#[test]
+fn malformed_lines_are_errors() {
+    for file in [
+        "v 1 2",
+        "v 1 two 3",
+        "vn 0 0",
+        "vn 0 0 1 1",
+        "vt",
+        "vt 0 0 0 0",
+        "f 1 2",
+        "g",
+    ] {
+        assert!(matches!(
+            ObjParser::parse(file),
+            Err(ObjError::MalformedLine { line: 1, .. })
+        ));
+    }
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    assert!(matches!(
+        ObjParser::from_file("does/not/exist.obj"),
+        Err(ObjError::Io(_))
+    ));
+}
+
+#[test]
+fn mesh_in_world() {
+    let file = "v -1 1 0\nv -1 -1 0\nv 1 -1 0\nv 1 1 0\nf 1 2 3 4";
+    let mut mesh = ObjParser::parse(file).unwrap().into_group();
+    mesh.translate(0.0, 0.0, 2.0);
+    let mut world = World::new();
+    world.add_object(mesh).unwrap();
+
+    let r = Ray::new(Tuple::point(0.5, 0.5, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut xs = world.intersect(&r);
+    let hit = *xs.hit().unwrap();
+    assert!(float_near_equal(hit.t, 7.0));
+    let comps = hit.prepare_computation(&r, &world);
+    assert_eq!(comps.normalv, Tuple::vector(0.0, 0.0, -1.0));
+}
@@ -45,20 +45,20 @@ fn lighting_with_pattern() {
     let normalv = Tuple::vector(0.0, 0.0, -1.0);
     let light = PointLight::new(Tuple::point(0.0, 0.0, -10.0), WHITE);
     let c1 = m.lighting(
-        &light,
+        &light.into(),
         &object,
         Tuple::point(0.9, 0.0, 0.0),
         eyev,
         normalv,
-        false,
+        1.0,
     );
     let c2 = m.lighting(
-        &light,
+        &light.into(),
         &object,
         Tuple::point(1.1, 0.0, 0.0),
         eyev,
         normalv,
-        false,
+        1.0,
     );
     assert_eq!(c1, WHITE);
     assert_eq!(c2, BLACK);
@@ -82,6 +82,15 @@ fn stripes_with_pattern_transform() {
     assert_eq!(c, WHITE);
 }
 
+#[test]
+#[should_panic(expected = "cannot be inverted")]
+fn stripes_with_flattened_pattern() {
+    let object = Shape::sphere();
+    let mut pattern = Pattern::stripes(WHITE, BLACK);
+    pattern.scale(0.0, 1.0, 1.0);
+    let _ = pattern.color_at_object(&object, Tuple::point(1.5, 0.0, 0.0));
+}
+
 #[test]
 fn stripes_with_both_transforms() {
     let mut object = Shape::sphere();
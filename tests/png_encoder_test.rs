@@ -0,0 +1,266 @@
+use ray_tracer::canvas::{Canvas, ImageFormat};
+use ray_tracer::color::Color;
+use ray_tracer::png_encoder::{PngBitDepth, PngEncoder};
+use ray_tracer::ppm_parser::PpmParser;
+
+fn chunks(png: &[u8]) -> Vec<(String, Vec<u8>)> {
+    let mut result = vec![];
+    let mut pos = 8;
+    while pos < png.len() {
+        let length = u32::from_be_bytes(png[pos..pos + 4].try_into().unwrap()) as usize;
+        let kind = String::from_utf8(png[pos + 4..pos + 8].to_vec()).unwrap();
+        result.push((kind, png[pos + 8..pos + 8 + length].to_vec()));
+        pos += length + 12;
+    }
+    assert_eq!(pos, png.len());
+    result
+}
+
+// Reads bits least significant first, as DEFLATE packs them.
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn bit(&mut self) -> u32 {
+        let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+        self.pos += 1;
+        u32::from(bit)
+    }
+
+    fn bits(&mut self, count: u32) -> u32 {
+        (0..count).fold(0, |acc, i| acc | self.bit() << i)
+    }
+
+    // Huffman codes are packed most significant bit first.
+    fn code(&mut self, length: u32) -> u32 {
+        (0..length).fold(0, |acc, _| acc << 1 | self.bit())
+    }
+
+    fn fixed_literal(&mut self) -> u32 {
+        let mut code = self.code(7);
+        if code <= 0x17 {
+            return 256 + code;
+        }
+        code = code << 1 | self.bit();
+        match code {
+            0x30..=0xbf => code - 0x30,
+            0xc0..=0xc7 => 280 + code - 0xc0,
+            _ => 144 + (code << 1 | self.bit()) - 0x190,
+        }
+    }
+}
+
+// Decompresses a zlib stream made of blocks using the fixed Huffman codes, which is all the
+// encoder writes, and checks its Adler-32 trailer.
+fn inflate(zlib: &[u8]) -> Vec<u8> {
+    const LENGTH_BASES: [usize; 29] = [
+        3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115,
+        131, 163, 195, 227, 258,
+    ];
+    const DISTANCE_BASES: [usize; 30] = [
+        1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+        2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+    ];
+    let length_extra = |code: usize| {
+        if (8..28).contains(&code) {
+            code as u32 / 4 - 1
+        } else {
+            0
+        }
+    };
+    let distance_extra = |code: usize| if code >= 4 { code as u32 / 2 - 1 } else { 0 };
+
+    assert_eq!(u16::from_be_bytes([zlib[0], zlib[1]]) % 31, 0);
+    let mut reader = BitReader {
+        data: zlib,
+        pos: 16,
+    };
+    let mut out: Vec<u8> = vec![];
+    loop {
+        let last = reader.bit() == 1;
+        assert_eq!(reader.bits(2), 1, "expected a block with fixed codes");
+        loop {
+            let symbol = reader.fixed_literal() as usize;
+            match symbol {
+                0..=255 => out.push(symbol as u8),
+                256 => break,
+                _ => {
+                    let code = symbol - 257;
+                    let length = LENGTH_BASES[code] + reader.bits(length_extra(code)) as usize;
+                    let code = reader.code(5) as usize;
+                    let distance =
+                        DISTANCE_BASES[code] + reader.bits(distance_extra(code)) as usize;
+                    assert!(distance <= out.len(), "back-reference before the start");
+                    for _ in 0..length {
+                        out.push(out[out.len() - distance]);
+                    }
+                }
+            }
+        }
+        if last {
+            break;
+        }
+    }
+
+    let trailer = reader.pos.div_ceil(8);
+    assert_eq!(zlib.len(), trailer + 4);
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in &out {
+        a = (a + u32::from(*byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    assert_eq!(zlib[trailer..], ((b << 16) | a).to_be_bytes());
+    out
+}
+
+// Reverses the filter at the start of each row, returning the rows without filter bytes.
+fn unfilter(data: &[u8], row_length: usize, bpp: usize) -> Vec<u8> {
+    assert_eq!(data.len() % (row_length + 1), 0);
+    let mut out: Vec<u8> = Vec::with_capacity(data.len());
+    for row in data.chunks(row_length + 1) {
+        let start = out.len();
+        for (i, byte) in row[1..].iter().enumerate() {
+            let left = if i >= bpp { out[start + i - bpp] } else { 0 };
+            let above = if start > 0 {
+                out[start + i - row_length]
+            } else {
+                0
+            };
+            let upper_left = if start > 0 && i >= bpp {
+                out[start + i - bpp - row_length]
+            } else {
+                0
+            };
+            let predicted = match row[0] {
+                0 => 0,
+                1 => left,
+                2 => above,
+                3 => ((u16::from(left) + u16::from(above)) / 2) as u8,
+                4 => {
+                    let p = i16::from(left) + i16::from(above) - i16::from(upper_left);
+                    let (pa, pb, pc) = (
+                        (p - i16::from(left)).abs(),
+                        (p - i16::from(above)).abs(),
+                        (p - i16::from(upper_left)).abs(),
+                    );
+                    if pa <= pb && pa <= pc {
+                        left
+                    } else if pb <= pc {
+                        above
+                    } else {
+                        upper_left
+                    }
+                }
+                filter => panic!("unknown filter type {filter}"),
+            };
+            out.push(byte.wrapping_add(predicted));
+        }
+    }
+    out
+}
+
+fn gradient(width: usize, height: usize) -> Canvas {
+    let mut c = Canvas::new(width, height);
+    for y in 0..height {
+        for x in 0..width {
+            let shades = [0.0, 0.2, 0.4, 0.6, 0.8];
+            c.write_pixel(x, y, Color::new(shades[x % 5], shades[y % 5], 1.0));
+        }
+    }
+    c
+}
+
+#[test]
+fn png_structure() {
+    let mut out = Vec::new();
+    PngEncoder::write(&gradient(20, 10), PngBitDepth::Eight, &mut out).unwrap();
+    assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+    let chunks = chunks(&out);
+    let kinds: Vec<&str> = chunks.iter().map(|(k, _)| k.as_str()).collect();
+    assert_eq!(kinds, ["IHDR", "IDAT", "IEND"]);
+    assert_eq!(chunks[0].1, [0, 0, 0, 20, 0, 0, 0, 10, 8, 2, 0, 0, 0]);
+    // A zlib stream using the default window size, which is smaller than the raw data.
+    assert_eq!(&chunks[1].1[..2], [0x78, 0x01]);
+    assert!(chunks[1].1.len() < 20 * 10 * 3);
+    assert!(chunks[2].1.is_empty());
+    // Every PNG ends with the same IEND chunk, including its checksum.
+    assert_eq!(
+        &out[out.len() - 12..],
+        [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+    );
+}
+
+#[test]
+fn png_image_data() {
+    let canvas = gradient(200, 50);
+    let mut out = Vec::new();
+    PngEncoder::write(&canvas, PngBitDepth::Eight, &mut out).unwrap();
+    let data = inflate(&chunks(&out)[1].1);
+    let expected: Vec<u8> = canvas
+        .pixels()
+        .iter()
+        .flat_map(|c| [c.red_to_int(), c.green_to_int(), c.blue_to_int()])
+        .collect();
+    assert_eq!(unfilter(&data, 200 * 3, 3), expected);
+}
+
+#[test]
+fn sixteen_bit_png() {
+    let canvas = gradient(3, 2);
+    let mut out = Vec::new();
+    PngEncoder::write(&canvas, PngBitDepth::Sixteen, &mut out).unwrap();
+    let chunks = chunks(&out);
+    assert_eq!(chunks[0].1, [0, 0, 0, 3, 0, 0, 0, 2, 16, 2, 0, 0, 0]);
+
+    let data = inflate(&chunks[1].1);
+    let expected: Vec<u8> = canvas
+        .pixels()
+        .iter()
+        .flat_map(|c| [c.red, c.green, c.blue])
+        .flat_map(|channel| ((channel * 65535.0).round() as u16).to_be_bytes())
+        .collect();
+    assert_eq!(unfilter(&data, 3 * 6, 6), expected);
+}
+
+#[test]
+fn empty_canvas_is_an_error() {
+    let mut out = Vec::new();
+    assert!(PngEncoder::write(&Canvas::new(0, 5), PngBitDepth::Eight, &mut out).is_err());
+}
+
+#[test]
+fn format_from_extension() {
+    assert_eq!(ImageFormat::from_path("out.png"), Some(ImageFormat::Png));
+    assert_eq!(
+        ImageFormat::from_path("dir/out.PNG"),
+        Some(ImageFormat::Png)
+    );
+    assert_eq!(ImageFormat::from_path("out.ppm"), Some(ImageFormat::Ppm));
+    assert_eq!(ImageFormat::from_path("out.jpg"), None);
+    assert_eq!(ImageFormat::from_path("png"), None);
+}
+
+#[test]
+fn saving_by_extension() {
+    let dir = std::env::temp_dir();
+    let canvas = gradient(4, 3);
+
+    let png = dir.join("ray_tracer_save_test.png");
+    canvas.save(png.to_str().unwrap()).unwrap();
+    assert!(std::fs::read(&png).unwrap().starts_with(b"\x89PNG"));
+
+    let ppm = dir.join("ray_tracer_save_test.ppm");
+    canvas.save(ppm.to_str().unwrap()).unwrap();
+    let read = PpmParser::from_file(ppm.to_str().unwrap()).unwrap();
+    assert_eq!(read.pixels(), canvas.pixels());
+
+    let unknown = dir.join("ray_tracer_save_test.bmp");
+    let err = canvas.save(unknown.to_str().unwrap()).unwrap_err();
+    assert!(err.to_string().contains("ray_tracer_save_test.bmp"));
+    assert!(!unknown.exists());
+
+    std::fs::remove_file(png).unwrap();
+    std::fs::remove_file(ppm).unwrap();
+}
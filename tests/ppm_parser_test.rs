@@ -0,0 +1,201 @@
+use ray_tracer::canvas::Canvas;
+use ray_tracer::color::Color;
+use ray_tracer::ppm_parser::{PpmError, PpmParser};
+use ray_tracer::ppm_printer::{PpmFormat, PpmPrinter};
+
+#[test]
+fn reading_plain_ppm() {
+    let ppm = "P3\n4 3\n255\n\
+               255 127 0  0 127 255  127 255 0  255 255 255\n\
+               0 0 0  255 0 0  0 255 0  0 0 255\n\
+               255 255 0  0 255 255  255 0 255  127 127 127\n";
+    let canvas = PpmParser::parse(ppm.as_bytes()).unwrap();
+    assert_eq!(canvas.width(), 4);
+    assert_eq!(canvas.height(), 3);
+    let expected = [
+        (0, 0, Color::new(1.0, 0.49803921568627, 0.0)),
+        (1, 0, Color::new(0.0, 0.49803921568627, 1.0)),
+        (2, 0, Color::new(0.49803921568627, 1.0, 0.0)),
+        (3, 0, Color::new(1.0, 1.0, 1.0)),
+        (0, 1, Color::new(0.0, 0.0, 0.0)),
+        (1, 1, Color::new(1.0, 0.0, 0.0)),
+        (2, 2, Color::new(1.0, 0.0, 1.0)),
+        (
+            3,
+            2,
+            Color::new(0.49803921568627, 0.49803921568627, 0.49803921568627),
+        ),
+    ];
+    for (x, y, color) in expected {
+        assert_eq!(canvas.pixel_at(x, y), color);
+    }
+}
+
+#[test]
+fn comments_and_whitespace() {
+    let ppm = "P3 # a comment after the magic number\n\
+               # a full line comment\n\
+               2\t1\r\n\
+               # another comment\n\
+               255\n\
+               255 255 255 # trailing comment\n\
+               \n   0   0\n0";
+    let canvas = PpmParser::parse(ppm.as_bytes()).unwrap();
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 1.0, 1.0));
+    assert_eq!(canvas.pixel_at(1, 0), Color::new(0.0, 0.0, 0.0));
+}
+
+#[test]
+fn rescaling_by_max_value() {
+    let ppm = "P3\n2 1\n100\n100 50 25  0 10 75\n";
+    let canvas = PpmParser::parse(ppm.as_bytes()).unwrap();
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.5, 0.25));
+    assert_eq!(canvas.pixel_at(1, 0), Color::new(0.0, 0.1, 0.75));
+}
+
+#[test]
+fn reading_raw_ppm() {
+    let mut ppm = b"P6\n# comment\n2 1\n255\n".to_vec();
+    ppm.extend_from_slice(&[255, 0, 51, 10, 32, 0]);
+    let canvas = PpmParser::parse(&ppm).unwrap();
+    assert_eq!(canvas.pixel_at(0, 0), Color::new(1.0, 0.0, 0.2));
+    assert_eq!(
+        canvas.pixel_at(1, 0),
+        Color::new(10.0 / 255.0, 32.0 / 255.0, 0.0)
+    );
+
+    let mut ppm = b"P6 1 1 65535\n".to_vec();
+    ppm.extend_from_slice(&[0xff, 0xff, 0x80, 0x00, 0x00, 0x00]);
+    let canvas = PpmParser::parse(&ppm).unwrap();
+    assert_eq!(
+        canvas.pixel_at(0, 0),
+        Color::new(1.0, 32768.0 / 65535.0, 0.0)
+    );
+}
+
+#[test]
+fn round_trip_through_printer() {
+    let mut canvas = Canvas::new(17, 5);
+    for y in 0..canvas.height() {
+        for x in 0..canvas.width() {
+            let shades = [0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
+            canvas.write_pixel(
+                x,
+                y,
+                Color::new(shades[x % 6], shades[y], shades[(x + y) % 6]),
+            );
+        }
+    }
+    for format in [PpmFormat::Ascii, PpmFormat::Binary] {
+        let mut out = Vec::new();
+        PpmPrinter::write(&canvas, format, &mut out).unwrap();
+        let read = PpmParser::parse(&out).unwrap();
+        assert_eq!(read.width(), 17);
+        assert_eq!(read.height(), 5);
+        assert_eq!(read.pixels(), canvas.pixels());
+    }
+}
+
+#[test]
+fn unsupported_formats() {
+    for data in ["P1\n1 1\n1\n", "P5\n1 1\n255\n", "", "hello"] {
+        assert!(matches!(
+            PpmParser::parse(data.as_bytes()),
+            Err(PpmError::UnsupportedFormat(_))
+        ));
+    }
+}
+
+#[test]
+fn malformed_headers() {
+    match PpmParser::parse(b"P3\n2 x\n255\n") {
+        Err(PpmError::Malformed { line, found, .. }) => {
+            assert_eq!(line, 2);
+            assert_eq!(found, "x");
+        }
+        other => panic!("expected a malformed header error, got {other:?}"),
+    }
+    for data in [
+        "P3\n2 2\n0\n",
+        "P3\n2 2\n70000\n",
+        "P3\n2 2",
+        "P3\n-2 2\n255\n",
+    ] {
+        assert!(matches!(
+            PpmParser::parse(data.as_bytes()),
+            Err(PpmError::Malformed { .. })
+        ));
+    }
+    let err = PpmParser::parse(b"P3\n2 2\n").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Expected a maximum color value on line 3, found \"end of file\""
+    );
+}
+
+#[test]
+fn truncated_data() {
+    assert!(matches!(
+        PpmParser::parse(b"P3\n2 2\n255\n0 0 0 1 1 1\n"),
+        Err(PpmError::Truncated {
+            expected: 12,
+            found: 6
+        })
+    ));
+    let mut ppm = b"P6\n2 2\n255\n".to_vec();
+    ppm.extend_from_slice(&[0; 7]);
+    let err = PpmParser::parse(&ppm).unwrap_err();
+    assert!(matches!(
+        err,
+        PpmError::Truncated {
+            expected: 12,
+            found: 7
+        }
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Image data ended after 7 of 12 color values"
+    );
+}
+
+#[test]
+fn oversized_header() {
+    assert!(matches!(
+        PpmParser::parse(b"P6\n2147483648 2147483648\n65535\n"),
+        Err(PpmError::Truncated { found: 0, .. })
+    ));
+    assert!(matches!(
+        PpmParser::parse(b"P6\n4294967295 4294967295\n255\n"),
+        Err(PpmError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn values_above_max_value() {
+    assert!(matches!(
+        PpmParser::parse(b"P3\n1 1\n15\n0 16 0\n"),
+        Err(PpmError::ValueOutOfRange {
+            index: 1,
+            value: 16,
+            max_value: 15
+        })
+    ));
+    let mut ppm = b"P6\n1 1\n100\n".to_vec();
+    ppm.extend_from_slice(&[0, 0, 101]);
+    assert!(matches!(
+        PpmParser::parse(&ppm),
+        Err(PpmError::ValueOutOfRange { index: 2, .. })
+    ));
+    assert!(matches!(
+        PpmParser::parse(b"P3\n1 1\n255\n0 zero 0\n"),
+        Err(PpmError::Malformed { line: 4, .. })
+    ));
+}
+
+#[test]
+fn missing_file_is_an_error() {
+    assert!(matches!(
+        PpmParser::from_file("does/not/exist.ppm"),
+        Err(PpmError::Io(_))
+    ));
+}
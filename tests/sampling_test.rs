@@ -0,0 +1,135 @@
+use ray_tracer::color::Color;
+use ray_tracer::float_near_equal;
+use ray_tracer::sampling::{
+    AdaptiveSampling, AdaptiveStats, ReconstructionFilter, SamplePattern, Sampler,
+};
+
+#[test]
+fn default_sampler_uses_pixel_centre() {
+    let s = Sampler::default();
+    assert_eq!(s.samples_per_pixel(), 1);
+    assert_eq!(s.pattern(), SamplePattern::Grid);
+    assert_eq!(s.filter(), ReconstructionFilter::Box);
+    assert_eq!(s.offsets(3, 7), vec![(0.5, 0.5)]);
+}
+
+#[test]
+fn grid_offsets() {
+    let s = Sampler::new(4, SamplePattern::Grid, ReconstructionFilter::Box);
+    assert_eq!(
+        s.offsets(0, 0),
+        vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+    );
+    let s = Sampler::new(3, SamplePattern::Grid, ReconstructionFilter::Box);
+    assert_eq!(
+        s.offsets(0, 0),
+        vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75)]
+    );
+}
+
+#[test]
+fn jittered_offsets_stay_in_their_cells() {
+    let s = Sampler::new(9, SamplePattern::Jittered, ReconstructionFilter::Box);
+    for (x, y) in [(0, 0), (1, 0), (17, 42)] {
+        let offsets = s.offsets(x, y);
+        assert_eq!(offsets.len(), 9);
+        for (i, (dx, dy)) in offsets.iter().enumerate() {
+            let (column, row) = ((i % 3) as f64, (i / 3) as f64);
+            assert!(*dx >= column / 3.0 && *dx < (column + 1.0) / 3.0);
+            assert!(*dy >= row / 3.0 && *dy < (row + 1.0) / 3.0);
+        }
+    }
+}
+
+#[test]
+fn random_offsets_are_reproducible_per_pixel() {
+    let s = Sampler::new(8, SamplePattern::Random, ReconstructionFilter::Box);
+    let offsets = s.offsets(5, 9);
+    assert_eq!(offsets.len(), 8);
+    assert!(offsets
+        .iter()
+        .all(|(dx, dy)| (0.0..1.0).contains(dx) && (0.0..1.0).contains(dy)));
+    assert_eq!(s.offsets(5, 9), offsets);
+    assert_ne!(s.offsets(9, 5), offsets);
+    assert_ne!(s.offsets(6, 9), offsets);
+}
+
+#[test]
+#[should_panic(expected = "at least one sample")]
+fn zero_samples() {
+    let _ = Sampler::new(0, SamplePattern::Grid, ReconstructionFilter::Box);
+}
+
+#[test]
+fn filter_weights() {
+    assert!(float_near_equal(
+        ReconstructionFilter::Box.weight(0.4, -0.3),
+        1.0
+    ));
+    assert!(float_near_equal(
+        ReconstructionFilter::Tent.weight(0.0, 0.0),
+        1.0
+    ));
+    assert!(float_near_equal(
+        ReconstructionFilter::Tent.weight(0.5, -0.5),
+        0.25
+    ));
+    assert!(float_near_equal(
+        ReconstructionFilter::Gaussian.weight(0.0, 0.0),
+        1.0
+    ));
+    assert!(float_near_equal(
+        ReconstructionFilter::Gaussian.weight(0.5, 0.0),
+        (-0.5f64).exp()
+    ));
+    assert!(
+        ReconstructionFilter::Gaussian.weight(0.1, 0.1)
+            > ReconstructionFilter::Gaussian.weight(0.4, 0.1)
+    );
+}
+
+#[test]
+fn combining_samples() {
+    let offsets = [(0.5, 0.5), (0.0, 0.5)];
+    let colors = [Color::new(1.0, 0.0, 0.0), Color::new(0.0, 1.0, 0.0)];
+
+    let s = Sampler::new(2, SamplePattern::Grid, ReconstructionFilter::Box);
+    assert_eq!(s.combine(&offsets, &colors), Color::new(0.5, 0.5, 0.0));
+
+    // The tent filter gives the sample on the edge half the weight of the central one.
+    let s = Sampler::new(2, SamplePattern::Grid, ReconstructionFilter::Tent);
+    assert_eq!(
+        s.combine(&offsets, &colors),
+        Color::new(2.0 / 3.0, 1.0 / 3.0, 0.0)
+    );
+
+    assert_eq!(
+        s.combine(&[(0.1, 0.9)], &[Color::new(0.2, 0.3, 0.4)]),
+        Color::new(0.2, 0.3, 0.4)
+    );
+}
+
+#[test]
+fn adaptive_color_differences() {
+    let a = AdaptiveSampling::new(0.1, 2);
+    let base = Color::new(0.5, 0.5, 0.5);
+    assert!(!a.differs(base, base));
+    assert!(!a.differs(base, Color::new(0.55, 0.45, 0.59)));
+    assert!(a.differs(base, Color::new(0.5, 0.5, 0.65)));
+    assert!(a.differs(Color::new(0.0, 2.0, 0.0), base));
+    assert_eq!(AdaptiveSampling::default(), AdaptiveSampling::new(0.1, 2));
+}
+
+#[test]
+fn adaptive_stats() {
+    let stats = AdaptiveStats {
+        pixels: 10,
+        refined_pixels: 2,
+        samples: 25,
+    };
+    assert!(float_near_equal(stats.samples_per_pixel(), 2.5));
+    assert!(float_near_equal(
+        AdaptiveStats::default().samples_per_pixel(),
+        0.0
+    ));
+}
@@ -25,6 +25,23 @@ fn intersect_scaled_sphere() {
     assert!(float_near_equal(xs[1].t, 7.0));
 }
 
+#[test]
+fn flattened_sphere_is_not_intersected() {
+    let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
+    let mut s = Shape::sphere();
+    s.set_id(0);
+    s.scale(0.0, 1.0, 1.0);
+    assert!(s.intersect(&r).is_none());
+}
+
+#[test]
+#[should_panic(expected = "cannot be inverted")]
+fn normal_on_flattened_sphere() {
+    let mut s = Shape::sphere();
+    s.scale(1.0, 0.0, 1.0);
+    let _ = s.normal_at(Tuple::point(1.0, 0.0, 0.0));
+}
+
 #[test]
 fn intersect_translated_sphere() {
     let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
@@ -0,0 +1,176 @@
+use std::f64::consts::PI;
+
+use ray_tracer::color::Color;
+use ray_tracer::float_near_equal;
+use ray_tracer::intersection::Intersection;
+use ray_tracer::light::Light;
+use ray_tracer::point_light::PointLight;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::Shape;
+use ray_tracer::spot_light::SpotLight;
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+use ray_tracer::{MAX_REFLECT_DEPTH, WHITE};
+
+fn downward_spot() -> SpotLight {
+    SpotLight::new(
+        Tuple::point(0.0, 10.0, 0.0),
+        Tuple::vector(0.0, -2.0, 0.0),
+        PI / 8.0,
+        PI / 4.0,
+        WHITE,
+    )
+}
+
+#[test]
+fn spot_light_init() {
+    let light = downward_spot();
+    assert_eq!(light.position, Tuple::point(0.0, 10.0, 0.0));
+    assert_eq!(light.direction, Tuple::vector(0.0, -1.0, 0.0));
+    assert!(float_near_equal(light.inner_angle, PI / 8.0));
+    assert!(float_near_equal(light.outer_angle, PI / 4.0));
+    assert_eq!(light.intensity, WHITE);
+}
+
+#[test]
+#[should_panic(expected = "inner angle")]
+fn inner_angle_outside_outer_angle() {
+    let _ = SpotLight::new(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(0.0, -1.0, 0.0),
+        PI / 4.0,
+        PI / 8.0,
+        WHITE,
+    );
+}
+
+#[test]
+#[should_panic(expected = "direction")]
+fn spot_light_without_direction() {
+    let _ = SpotLight::new(
+        Tuple::point(0.0, 0.0, 0.0),
+        Tuple::vector(0.0, 0.0, 0.0),
+        PI / 8.0,
+        PI / 4.0,
+        WHITE,
+    );
+}
+
+#[test]
+fn spot_light_falloff() {
+    let light = downward_spot();
+    assert!(float_near_equal(
+        light.falloff(Tuple::point(0.0, 0.0, 0.0)),
+        1.0
+    ));
+    // Just inside the inner cone, at about 21 degrees from the axis.
+    assert!(float_near_equal(
+        light.falloff(Tuple::point(3.9, 0.0, 0.0)),
+        1.0
+    ));
+    // Outside the outer cone, and behind the light.
+    assert!(float_near_equal(
+        light.falloff(Tuple::point(10.5, 0.0, 0.0)),
+        0.0
+    ));
+    assert!(float_near_equal(
+        light.falloff(Tuple::point(0.0, 20.0, 0.0)),
+        0.0
+    ));
+
+    // Halfway between the cosines of the two angles the smooth step is exactly one half.
+    let cos = (f64::cos(PI / 8.0) + f64::cos(PI / 4.0)) / 2.0;
+    let angle = cos.acos();
+    let p = Tuple::point(10.0 * angle.tan(), 0.0, 0.0);
+    assert!(float_near_equal(light.falloff(p), 0.5));
+
+    let mut previous = 1.0;
+    for i in 0..=20 {
+        let falloff = light.falloff(Tuple::point(f64::from(i) * 0.6, 0.0, 0.0));
+        assert!(falloff <= previous);
+        previous = falloff;
+    }
+}
+
+#[test]
+fn falloff_at_the_light() {
+    let light = downward_spot();
+    assert!(float_near_equal(light.falloff(light.position), 1.0));
+}
+
+#[test]
+fn lighting_with_spot_light() {
+    let object = Shape::sphere();
+    let m = object.material();
+    let eyev = Tuple::vector(0.0, 1.0, 0.0);
+    let normalv = Tuple::vector(0.0, 1.0, 0.0);
+    let light = downward_spot();
+    let point = PointLight::new(light.position, light.intensity);
+
+    // In the middle of the cone a spot light behaves like a point light.
+    let p = Tuple::point(0.0, 0.0, 0.0);
+    assert_eq!(
+        m.lighting(&light.into(), &object, p, eyev, normalv, 1.0),
+        m.lighting(&point.into(), &object, p, eyev, normalv, 1.0)
+    );
+
+    // Outside the cone only the ambient light is left.
+    let p = Tuple::point(20.0, 0.0, 0.0);
+    assert_eq!(
+        m.lighting(&light.into(), &object, p, eyev, normalv, 1.0),
+        Color::new(0.1, 0.1, 0.1)
+    );
+    assert_eq!(
+        m.lighting(
+            &light.into(),
+            &object,
+            Tuple::point(0.0, 0.0, 0.0),
+            eyev,
+            normalv,
+            0.0
+        ),
+        Color::new(0.1, 0.1, 0.1)
+    );
+}
+
+#[test]
+fn spot_light_shadows() {
+    let w = World::default();
+    let light = SpotLight::new(
+        Tuple::point(-10.0, 10.0, -10.0),
+        Tuple::vector(1.0, -1.0, 1.0),
+        PI / 8.0,
+        PI / 6.0,
+        WHITE,
+    );
+    assert!(!w.is_shadowed(Tuple::point(0.0, 10.0, 0.0), &light.into()));
+    assert!(w.is_shadowed(Tuple::point(10.0, -10.0, 10.0), &light.into()));
+    assert!(!w.is_shadowed(Tuple::point(-2.0, 2.0, -2.0), &light.into()));
+}
+
+#[test]
+fn worlds_mix_spot_and_point_lights() {
+    let mut w = World::new();
+    let mut floor = Shape::plane();
+    floor.set_specular(0.0);
+    w.add_object(floor).unwrap();
+    let id = w.add_light(downward_spot());
+    assert_eq!(id, 0);
+    assert_eq!(w.get_light(0), Some(&Light::Spot(downward_spot())));
+    assert_eq!(w.get_light(1), None);
+
+    let shade = |w: &World, x: f64| {
+        let r = Ray::new(Tuple::point(x, 1.0, 0.0), Tuple::vector(0.0, -1.0, 0.0));
+        let comps = Intersection::new(1.0, 0).prepare_computation(&r, w);
+        w.shade_hit(&comps, MAX_REFLECT_DEPTH).red
+    };
+    // Fully lit under the light, ambient only well outside the cone.
+    assert!(float_near_equal(shade(&w, 0.0), 1.0));
+    assert!(float_near_equal(shade(&w, 20.0), 0.1));
+    let edge = shade(&w, 7.0);
+    assert!(edge > 0.1 && edge < 1.0);
+
+    w.add_light(PointLight::new(Tuple::point(20.0, 10.0, 0.0), WHITE));
+    assert!(shade(&w, 20.0) > 0.9);
+    assert!(shade(&w, 0.0) > 1.0);
+}
@@ -0,0 +1,138 @@
+use ray_tracer::canvas::Canvas;
+use ray_tracer::color::Color;
+use ray_tracer::float_near_equal;
+use ray_tracer::ppm_parser::PpmParser;
+use ray_tracer::ppm_printer::{PpmFormat, PpmPrinter};
+use ray_tracer::tone_map::{srgb_decode, srgb_encode, ToneMap, ToneMapOperator, TransferFunction};
+
+#[test]
+fn default_tone_map_only_clamps() {
+    let t = ToneMap::default();
+    assert_eq!(
+        t.map_color(Color::new(0.25, 0.5, 0.75)),
+        Color::new(0.25, 0.5, 0.75)
+    );
+    assert_eq!(
+        t.map_color(Color::new(-1.0, 1.5, 1.0)),
+        Color::new(0.0, 1.0, 1.0)
+    );
+}
+
+#[test]
+fn exposure_is_measured_in_stops() {
+    let t = ToneMap::new(1.0, ToneMapOperator::Clamp, TransferFunction::Linear);
+    assert_eq!(
+        t.map_color(Color::new(0.1, 0.2, 0.6)),
+        Color::new(0.2, 0.4, 1.0)
+    );
+    let t = ToneMap::new(-2.0, ToneMapOperator::Clamp, TransferFunction::Linear);
+    assert_eq!(
+        t.map_color(Color::new(2.0, 1.0, 0.0)),
+        Color::new(0.5, 0.25, 0.0)
+    );
+}
+
+#[test]
+fn reinhard_compresses_highlights() {
+    let t = ToneMap::new(0.0, ToneMapOperator::Reinhard, TransferFunction::Linear);
+    assert_eq!(
+        t.map_color(Color::new(1.0, 3.0, 0.0)),
+        Color::new(0.5, 0.75, 0.0)
+    );
+    let bright = t.map_color(Color::new(1000.0, 1000.0, 1000.0));
+    assert!(bright.red < 1.0 && bright.red > 0.99);
+    assert_eq!(
+        t.map_color(Color::new(-2.0, 0.0, 0.0)),
+        Color::new(0.0, 0.0, 0.0)
+    );
+}
+
+#[test]
+fn aces_is_monotonic_and_bounded() {
+    let t = ToneMap::new(0.0, ToneMapOperator::Aces, TransferFunction::Linear);
+    assert_eq!(
+        t.map_color(Color::new(0.0, 0.0, 0.0)),
+        Color::new(0.0, 0.0, 0.0)
+    );
+    assert_eq!(
+        t.map_color(Color::new(100.0, 100.0, 100.0)),
+        Color::new(1.0, 1.0, 1.0)
+    );
+    let mut previous = 0.0;
+    for i in 1..100 {
+        let value = t.map_color(Color::new(f64::from(i) * 0.1, 0.0, 0.0)).red;
+        assert!(value >= previous);
+        previous = value;
+    }
+    let grey = 0.18 * (2.51 * 0.18 + 0.03) / (0.18 * (2.43 * 0.18 + 0.59) + 0.14);
+    assert!(float_near_equal(
+        t.map_color(Color::new(0.18, 0.18, 0.18)).red,
+        grey
+    ));
+}
+
+#[test]
+fn srgb_transfer_function() {
+    assert!(float_near_equal(srgb_encode(0.0), 0.0));
+    assert!(float_near_equal(srgb_encode(1.0), 1.0));
+    assert!(float_near_equal(srgb_encode(0.002), 0.002 * 12.92));
+    assert!((srgb_encode(0.5) - 0.735_356_983).abs() < 1e-6);
+    for i in 0..=100 {
+        let value = f64::from(i) / 100.0;
+        assert!(float_near_equal(srgb_decode(srgb_encode(value)), value));
+    }
+}
+
+#[test]
+fn srgb_is_applied_after_tone_mapping() {
+    let t = ToneMap::new(0.0, ToneMapOperator::Reinhard, TransferFunction::Srgb);
+    let expected = srgb_encode(0.5);
+    assert_eq!(
+        t.map_color(Color::new(1.0, 1.0, 1.0)),
+        Color::new(expected, expected, expected)
+    );
+}
+
+#[test]
+fn applying_to_a_canvas() {
+    let mut c = Canvas::new(3, 2);
+    c.write_pixel(2, 1, Color::new(3.0, 1.0, 0.0));
+    let t = ToneMap::new(0.0, ToneMapOperator::Reinhard, TransferFunction::Linear);
+    let mapped = t.apply(&c);
+    assert_eq!(mapped.width(), 3);
+    assert_eq!(mapped.height(), 2);
+    assert_eq!(mapped.pixel_at(2, 1), Color::new(0.75, 0.5, 0.0));
+    assert_eq!(mapped.pixel_at(0, 0), Color::new(0.0, 0.0, 0.0));
+}
+
+#[test]
+fn writing_tone_mapped_ppm() {
+    let mut c = Canvas::new(2, 1);
+    c.write_pixel(0, 0, Color::new(1.0, 3.0, 0.0));
+    c.write_pixel(1, 0, Color::new(0.5, 0.25, 2.0));
+    let t = ToneMap::new(0.0, ToneMapOperator::Reinhard, TransferFunction::Linear);
+    for format in [PpmFormat::Ascii, PpmFormat::Binary] {
+        let mut mapped = Vec::new();
+        PpmPrinter::write_tone_mapped(&c, format, &t, &mut mapped).unwrap();
+        let mut expected = Vec::new();
+        PpmPrinter::write(&t.apply(&c), format, &mut expected).unwrap();
+        assert_eq!(mapped, expected);
+    }
+}
+
+#[test]
+fn saving_tone_mapped_images() {
+    let mut c = Canvas::new(2, 1);
+    c.write_pixel(0, 0, Color::new(3.0, 1.0, 0.0));
+    let t = ToneMap::new(0.0, ToneMapOperator::Reinhard, TransferFunction::Linear);
+    let path = std::env::temp_dir().join("ray_tracer_tone_map_test.ppm");
+    let path = path.to_str().unwrap();
+    c.save_tone_mapped(path, &t).unwrap();
+    // Removed before asserting, so a failure does not leave the file behind.
+    let read = PpmParser::from_file(path);
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(
+        read.unwrap().pixel_at(0, 0),
+        Color::new(191.0 / 255.0, 128.0 / 255.0, 0.0)
+    );
+}
@@ -1,6 +1,6 @@
 use std::f64::consts::PI;
 
-use ray_tracer::matrix::Matrix;
+use ray_tracer::matrix::Matrix4;
 use ray_tracer::ray::Ray;
 use ray_tracer::transform::Transform;
 use ray_tracer::tuple::Tuple;
@@ -215,26 +215,26 @@ fn view_transform() {
     up = Tuple::vector(1.0, 1.0, 0.0);
     t = Transform::view_transform(&from, &to, &up);
 
-    let expected = Matrix::new(vec![
-        vec![
+    let expected = Matrix4::new([
+        [
             -0.5070925528371099,
             0.5070925528371099,
             0.6761234037828132,
             -2.366431913239846,
         ],
-        vec![
+        [
             0.7677159338596801,
             0.6060915267313263,
             0.12121830534626524,
             -2.8284271247461894,
         ],
-        vec![
+        [
             -0.35856858280031806,
             0.5976143046671968,
             -0.7171371656006361,
             0.0,
         ],
-        vec![0.0, 0.0, 0.0, 1.0],
+        [0.0, 0.0, 0.0, 1.0],
     ]);
 
     assert_eq!(t.matrix, expected);
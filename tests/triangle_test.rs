@@ -0,0 +1,104 @@
+use ray_tracer::float_near_equal;
+use ray_tracer::intersection::Intersection;
+use ray_tracer::ray::Ray;
+use ray_tracer::shape::{Shape, ShapeType};
+use ray_tracer::tuple::Tuple;
+use ray_tracer::world::World;
+
+fn test_triangle() -> Shape {
+    Shape::triangle(
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::point(-1.0, 0.0, 0.0),
+        Tuple::point(1.0, 0.0, 0.0),
+    )
+}
+
+fn test_smooth_triangle() -> Shape {
+    Shape::smooth_triangle(
+        Tuple::point(0.0, 1.0, 0.0),
+        Tuple::point(-1.0, 0.0, 0.0),
+        Tuple::point(1.0, 0.0, 0.0),
+        Tuple::vector(0.0, 1.0, 0.0),
+        Tuple::vector(-1.0, 0.0, 0.0),
+        Tuple::vector(1.0, 0.0, 0.0),
+    )
+}
+
+#[test]
+fn construct_triangle() {
+    let t = test_triangle();
+    match *t.kind() {
+        ShapeType::Triangle { e1, e2, normal, .. } => {
+            assert_eq!(e1, Tuple::vector(-1.0, -1.0, 0.0));
+            assert_eq!(e2, Tuple::vector(1.0, -1.0, 0.0));
+            assert_eq!(normal, Tuple::vector(0.0, 0.0, -1.0));
+        }
+        _ => panic!("expected a triangle"),
+    }
+}
+
+#[test]
+fn triangle_normal_is_constant() {
+    let t = test_triangle();
+    let n = Tuple::vector(0.0, 0.0, -1.0);
+    assert_eq!(t.normal_at(Tuple::point(0.0, 0.5, 0.0)), n);
+    assert_eq!(t.normal_at(Tuple::point(-0.5, 0.75, 0.0)), n);
+    assert_eq!(t.normal_at(Tuple::point(0.5, 0.25, 0.0)), n);
+}
+
+#[test]
+fn ray_misses_triangle() {
+    let mut t = test_triangle();
+    t.set_id(0);
+    let cases = [
+        (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 1.0, 0.0)),
+        (Tuple::point(1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+        (Tuple::point(-1.0, 1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+        (Tuple::point(0.0, -1.0, -2.0), Tuple::vector(0.0, 0.0, 1.0)),
+    ];
+    for (origin, direction) in cases {
+        let xs = t.intersect(&Ray::new(origin, direction)).unwrap();
+        assert_eq!(xs.len(), 0);
+    }
+}
+
+#[test]
+fn ray_strikes_triangle() {
+    let mut t = test_triangle();
+    t.set_id(0);
+    let r = Ray::new(Tuple::point(0.0, 0.5, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = t.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 1);
+    assert!(float_near_equal(xs[0].t, 2.0));
+}
+
+#[test]
+fn smooth_triangle_intersection_stores_uv() {
+    let mut t = test_smooth_triangle();
+    t.set_id(0);
+    let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let xs = t.intersect(&r).unwrap();
+    assert_eq!(xs.len(), 1);
+    assert!(float_near_equal(xs[0].u, 0.45));
+    assert!(float_near_equal(xs[0].v, 0.25));
+}
+
+#[test]
+fn smooth_triangle_interpolates_normal() {
+    let t = test_smooth_triangle();
+    let i = Intersection::with_uv(1.0, 0, 0.45, 0.25);
+    assert_eq!(
+        t.normal_at_hit(Tuple::point(0.0, 0.0, 0.0), &i),
+        Tuple::vector(-0.2, 0.3, 0.0).normalize()
+    );
+}
+
+#[test]
+fn prepare_computation_uses_smooth_normal() {
+    let mut world = World::new();
+    let id = world.add_object(test_smooth_triangle()).unwrap();
+    let r = Ray::new(Tuple::point(-0.2, 0.3, -2.0), Tuple::vector(0.0, 0.0, 1.0));
+    let i = Intersection::with_uv(1.0, id, 0.45, 0.25);
+    let comps = i.prepare_computation(&r, &world);
+    assert_eq!(comps.normalv, Tuple::vector(-0.2, 0.3, 0.0).normalize());
+}
@@ -1,10 +1,12 @@
 use ray_tracer::color::Color;
 use ray_tracer::float_near_equal;
 use ray_tracer::intersection::{Intersection, IntersectionList};
+use ray_tracer::light::Light;
 use ray_tracer::pattern::Pattern;
 use ray_tracer::point_light::PointLight;
 use ray_tracer::ray::Ray;
 use ray_tracer::shape::Shape;
+use ray_tracer::transform::DegenerateTransform;
 use ray_tracer::tuple::Tuple;
 use ray_tracer::world::World;
 use ray_tracer::MAX_REFLECT_DEPTH;
@@ -29,7 +31,7 @@ fn default_world() {
     s2.scale(0.5, 0.5, 0.5);
 
     let world = World::default();
-    assert_eq!(world.get_light(0).unwrap(), &light);
+    assert_eq!(world.get_light(0).unwrap(), &Light::Point(light));
     assert_eq!(world.get_object(0).unwrap(), &s1);
     assert_eq!(world.get_object(1).unwrap(), &s2);
 }
@@ -64,7 +66,7 @@ fn hit_shading() {
 
     w.set_light(
         0,
-        &mut PointLight::new(Tuple::point(0.0, 0.25, 0.0), Color::new(1.0, 1.0, 1.0)),
+        PointLight::new(Tuple::point(0.0, 0.25, 0.0), Color::new(1.0, 1.0, 1.0)),
     )
     .unwrap();
 
@@ -87,8 +89,8 @@ fn shade_hit_in_shadow() {
     let s1 = Shape::sphere();
     let mut s2 = Shape::sphere();
     s2.translate(0.0, 0.0, 10.0);
-    w.add_object(s1);
-    let s2_id = w.add_object(s2);
+    w.add_object(s1).unwrap();
+    let s2_id = w.add_object(s2).unwrap();
     let r = Ray::new(Tuple::point(0.0, 0.0, 5.0), Tuple::vector(0.0, 0.0, 1.0));
     let i = Intersection::new(4.0, s2_id);
     let comps = i.prepare_computation(&r, &w);
@@ -108,8 +110,8 @@ fn reflect_on_nonreflective() {
     s2.scale(0.5, 0.5, 0.5);
     s2.set_ambient(1.0);
     let mut new_world = World::new();
-    new_world.add_object(s1);
-    new_world.add_object(s2);
+    new_world.add_object(s1).unwrap();
+    new_world.add_object(s2).unwrap();
     new_world.add_light(light);
 
     let i = Intersection::new(1.0, 1);
@@ -124,7 +126,7 @@ fn reflect_on_reflective() {
     let mut shape = Shape::plane();
     shape.set_reflective(0.5);
     shape.translate(0.0, -1.0, 0.0);
-    w.add_object(shape);
+    w.add_object(shape).unwrap();
     let r = Ray::new(
         Tuple::point(0.0, 0.0, -3.0),
         Tuple::vector(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
@@ -142,7 +144,7 @@ fn shade_hit_with_reflective() {
     let mut shape = Shape::plane();
     shape.set_reflective(0.5);
     shape.translate(0.0, -1.0, 0.0);
-    w.add_object(shape);
+    w.add_object(shape).unwrap();
     let r = Ray::new(
         Tuple::point(0.0, 0.0, -3.0),
         Tuple::vector(0.0, -(2_f64.sqrt()) / 2.0, 2_f64.sqrt() / 2.0),
@@ -164,8 +166,8 @@ fn color_at_avoid_infinite_recursion() {
     let mut upper = Shape::plane();
     upper.set_reflective(1.0);
     upper.translate(0.0, 1.0, 0.0);
-    w.add_object(lower);
-    w.add_object(upper);
+    w.add_object(lower).unwrap();
+    w.add_object(upper).unwrap();
     w.add_light(light);
     w.color_at(
         &Ray::new(Tuple::point(0.0, 0.0, 0.0), Tuple::vector(0.0, 1.0, 0.0)),
@@ -195,8 +197,8 @@ fn refracted_color_at_maximum_depth() {
     let mut s2 = Shape::sphere();
     s2.scale(0.5, 0.5, 0.5);
     let mut new_world = World::new();
-    new_world.add_object(s1);
-    new_world.add_object(s2);
+    new_world.add_object(s1).unwrap();
+    new_world.add_object(s2).unwrap();
     new_world.add_light(light);
 
     let r = Ray::new(Tuple::point(0.0, 0.0, -5.0), Tuple::vector(0.0, 0.0, 1.0));
@@ -218,8 +220,8 @@ fn test_internal_reflection() {
     let mut s2 = Shape::sphere();
     s2.scale(0.5, 0.5, 0.5);
     let mut new_world = World::new();
-    new_world.add_object(s1);
-    new_world.add_object(s2);
+    new_world.add_object(s1).unwrap();
+    new_world.add_object(s2).unwrap();
     new_world.add_light(light);
 
     let r = Ray::new(
@@ -250,8 +252,8 @@ fn refracted_color() {
     s2.set_transparency(1.0);
     s2.set_refractive_index(1.5);
     let mut new_world = World::new();
-    new_world.add_object(s1);
-    new_world.add_object(s2);
+    new_world.add_object(s1).unwrap();
+    new_world.add_object(s2).unwrap();
     new_world.add_light(light);
 
     let r = Ray::new(Tuple::point(0.0, 0.0, 0.1), Tuple::vector(0.0, 1.0, 0.0));
@@ -270,7 +272,7 @@ fn refracted_color() {
 fn schlick_test() {
     let s = Shape::glass_sphere();
     let mut world = World::new();
-    world.add_object(s);
+    world.add_object(s).unwrap();
     let mut r = Ray::new(
         Tuple::point(0.0, 0.0, 2_f64.sqrt() / 2.0),
         Tuple::vector(0.0, 1.0, 0.0),
@@ -308,13 +310,13 @@ fn render_with_schlick() {
     floor.set_reflective(0.5);
     floor.set_transparency(0.5);
     floor.set_refractive_index(1.5);
-    w.add_object(floor);
+    w.add_object(floor).unwrap();
 
     let mut ball = Shape::sphere();
     ball.set_color(Color::new(1.0, 0.0, 0.0));
     ball.set_ambient(0.5);
     ball.translate(0.0, -3.5, -0.5);
-    w.add_object(ball);
+    w.add_object(ball).unwrap();
 
     let xs = IntersectionList::new(&vec![Intersection::new(2_f64.sqrt(), 2)]);
     let comps = xs.prepare_computation(0, &r, &w);
@@ -336,21 +338,21 @@ fn shade_hit_with_transparent_material() {
     s2.set_transparency(1.0);
     s2.set_refractive_index(1.5);
     let mut new_world = World::new();
-    new_world.add_object(s1);
-    new_world.add_object(s2);
+    new_world.add_object(s1).unwrap();
+    new_world.add_object(s2).unwrap();
     new_world.add_light(light);
 
     let mut floor = Shape::plane();
     floor.translate(0.0, -1.0, 0.0);
     floor.set_transparency(0.5);
     floor.set_refractive_index(1.5);
-    new_world.add_object(floor);
+    new_world.add_object(floor).unwrap();
 
     let mut ball = Shape::sphere();
     ball.set_color(Color::new(1.0, 0.0, 0.0));
     ball.set_ambient(0.5);
     ball.translate(0.0, -3.5, -0.5);
-    new_world.add_object(ball);
+    new_world.add_object(ball).unwrap();
 
     let r = Ray::new(
         Tuple::point(0.0, 0.0, -3.0),
@@ -397,8 +399,8 @@ fn intersection_behind_ray() {
     let mut s2 = Shape::sphere();
     s2.scale(0.5, 0.5, 0.5);
     s2.set_ambient(1.0);
-    w.add_object(s1);
-    w.add_object(s2.clone());
+    w.add_object(s1).unwrap();
+    w.add_object(s2.clone()).unwrap();
     w.add_light(light);
 
     let r = Ray::new(Tuple::point(0.0, 0.0, 0.75), Tuple::vector(0.0, 0.0, -1.0));
@@ -421,3 +423,30 @@ fn is_shadowed() {
     p = Tuple::point(-2.0, 2.0, -2.0);
     assert!(!w.is_shadowed(p, w.get_light(0).unwrap()));
 }
+
+#[test]
+fn degenerate_transforms_are_rejected() {
+    let mut w = World::new();
+    let mut flat = Shape::sphere();
+    flat.scale(1.0, 0.0, 1.0);
+    let err = w.add_object(flat).unwrap_err();
+    assert_eq!(err, DegenerateTransform::Object(0));
+    assert_eq!(err.to_string(), "Object 0 has a degenerate transform");
+    assert_eq!(w.num_objects(), 0);
+
+    let mut group = Shape::group();
+    group.add_child(Shape::sphere());
+    let mut flat = Shape::cube();
+    flat.scale(0.0, 1.0, 1.0);
+    group.add_child(flat);
+    assert_eq!(w.add_object(group), Err(DegenerateTransform::Object(2)));
+
+    let mut s = Shape::sphere();
+    let mut pattern = Pattern::stripes(Color::new(1.0, 1.0, 1.0), Color::new(0.0, 0.0, 0.0));
+    pattern.scale(0.0, 0.0, 0.0);
+    s.set_pattern(&pattern);
+    assert_eq!(w.add_object(s), Err(DegenerateTransform::Pattern(0)));
+
+    assert_eq!(w.add_object(Shape::sphere()), Ok(0));
+    assert_eq!(w.num_objects(), 1);
+}